@@ -0,0 +1,62 @@
+// turns gnu unifont (fonts/unifont.hex, "codepoint:bitmap" per line) into a table compiled into the binary,
+// only the ranges the mono fonts don't cover that file names are likely to use.
+// without the file everything still builds, those chars just show as boxes
+use std::{env, fs, path::Path};
+
+const UNIFONT_PATH: &str = "fonts/unifont.hex";
+const RANGES: [(u32, u32); 4] = [
+    // cjk punctuation, hiragana, katakana
+    (0x3000, 0x30FF),
+    // cjk unified ideographs
+    (0x4E00, 0x9FFF),
+    // hangul syllables
+    (0xAC00, 0xD7A3),
+    // fullwidth forms
+    (0xFF00, 0xFFEF),
+];
+
+fn main() {
+    let unifont = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(UNIFONT_PATH);
+    println!("cargo:rerun-if-changed={}", unifont.display());
+    let mut glyphs: Vec<(u32, Vec<u8>)> = match fs::read_to_string(&unifont) {
+        Ok(contents) => contents.lines()
+            .filter_map(parse_glyph)
+            .filter(|(codepoint, _)| RANGES.iter().any(|(first, last)| (first..=last).contains(&codepoint)))
+            .collect(),
+        Err(e) => {
+            println!("cargo:warning=no {}, cjk etc will show as boxes: {}", unifont.display(), e);
+            Vec::new()
+        }
+    };
+    glyphs.sort_by_key(|(codepoint, _)| *codepoint);
+
+    // bitmaps back to back in one blob, the index says where each one starts
+    let mut index = String::new();
+    let mut bits = Vec::new();
+    for (codepoint, bitmap) in glyphs {
+        // 16 rows either way, so the width falls out of the length
+        index.push_str(&format!("    ({:#x}, {}, {}),\n", codepoint, bitmap.len() / 2, bits.len()));
+        bits.extend(bitmap);
+    }
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("wide_glyphs.bin"), &bits).unwrap();
+    let table = format!(
+        "// codepoint, width, offset into WIDE_BITS. sorted by codepoint\n\
+         static WIDE_GLYPHS: &[(u32, u8, u32)] = &[\n{}];\n\
+         static WIDE_BITS: &[u8] = include_bytes!(concat!(env!(\"OUT_DIR\"), \"/wide_glyphs.bin\"));\n",
+        index,
+    );
+    fs::write(Path::new(&out_dir).join("wide_glyphs.rs"), table).unwrap();
+}
+
+// "4E2D:0100010001003FF8..." - 32 hex digits for 8x16, 64 for 16x16
+fn parse_glyph(line: &str) -> Option<(u32, Vec<u8>)> {
+    let (codepoint, bitmap) = line.split_once(':')?;
+    if bitmap.len() != 32 && bitmap.len() != 64 {
+        return None;
+    }
+    let bits = (0..bitmap.len()).step_by(2)
+        .map(|idx| u8::from_str_radix(bitmap.get(idx..idx + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some((u32::from_str_radix(codepoint, 16).ok()?, bits))
+}
@@ -0,0 +1,28 @@
+use std::time::{Duration, Instant};
+use embedded_graphics::prelude::*;
+
+// how often the draw task wakes up while something's moving, about as fast as the spi screen keeps up w/
+pub const FRAME: Duration = Duration::from_millis(25);
+// short enough that holding a button down still feels instant
+const SLIDE_FOR: Duration = Duration::from_millis(160);
+
+// something moving from an offset back to where it belongs, fast at first then settling
+pub struct Slide {
+    from: Point,
+    started: Instant,
+}
+impl Slide {
+    pub fn new(from: Point) -> Slide {
+        Slide { from, started: Instant::now() }
+    }
+    // how far off it is right now, None once it's done
+    pub fn offset(&self) -> Option<Point> {
+        let elapsed = self.started.elapsed();
+        if elapsed >= SLIDE_FOR {
+            return None;
+        }
+        let left = 1.0 - elapsed.as_secs_f32() / SLIDE_FOR.as_secs_f32();
+        let eased = left * left * left;
+        Some(Point::new((self.from.x as f32 * eased) as i32, (self.from.y as f32 * eased) as i32))
+    }
+}
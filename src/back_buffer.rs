@@ -0,0 +1,102 @@
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+// everything for the spi screen is drawn in here first, then only the parts that changed
+// get copied to /dev/fb1 once per tick, so half drawn screens never show up
+pub struct BackBuffer {
+    pub buf: Vec<u8>,
+    width: usize,
+    height: usize,
+    dirty: Vec<Rectangle>,
+    // percent, the backlight is only on/off so anything under 100 dims the pixels on the way out
+    brightness: u8,
+    // pixels drawn over buf on the way out w/o touching it, so whatever's under comes back once it's gone
+    overlay: Option<(Rectangle, Vec<u8>)>,
+}
+impl BackBuffer {
+    pub fn new(width: usize, height: usize) -> BackBuffer {
+        BackBuffer { buf: vec![0u8; width * height * 2], width, height, dirty: Vec::new(), brightness: 100, overlay: None }
+    }
+    fn bounds(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), Size::new(self.width as u32, self.height as u32))
+    }
+    pub fn mark(&mut self, rect: Rectangle) {
+        let rect = rect.intersection(&self.bounds());
+        if rect.is_zero_sized() {
+            return;
+        }
+        // already covered by something bigger, happens a lot w/ full redraws
+        if self.dirty.iter().any(|dirty| dirty.intersection(&rect) == rect) {
+            return;
+        }
+        self.dirty.retain(|dirty| rect.intersection(dirty) != *dirty);
+        self.dirty.push(rect);
+    }
+    pub fn mark_rects(&mut self, rects: Vec<Rectangle>) {
+        for rect in rects {
+            self.mark(rect);
+        }
+    }
+    pub fn mark_all(&mut self) {
+        self.dirty = vec![self.bounds()];
+    }
+    // pixels is the size of the rect, None takes the old one away
+    pub fn set_overlay(&mut self, overlay: Option<(Rectangle, Vec<u8>)>) {
+        if let Some((rect, _)) = &self.overlay {
+            self.mark(*rect);
+        }
+        if let Some((rect, _)) = &overlay {
+            self.mark(*rect);
+        }
+        self.overlay = overlay;
+    }
+    pub fn set_brightness(&mut self, percent: u8) {
+        self.brightness = percent.min(100);
+        self.mark_all();
+    }
+    // copies the dirty rects row by row, full width ones in one go, then the overlay over any part of them it covers
+    pub fn flush(&mut self, fb: &mut [u8]) {
+        let row_bytes = self.width * 2;
+        for rect in std::mem::take(&mut self.dirty) {
+            let top = rect.top_left.y as usize;
+            let rows = rect.size.height as usize;
+            if rect.size.width as usize == self.width {
+                copy_dimmed(&mut fb[top * row_bytes..(top + rows) * row_bytes], &self.buf[top * row_bytes..(top + rows) * row_bytes], self.brightness);
+            }
+            else {
+                let left = rect.top_left.x as usize * 2;
+                let span = rect.size.width as usize * 2;
+                for y in top..top + rows {
+                    let start = y * row_bytes + left;
+                    copy_dimmed(&mut fb[start..start + span], &self.buf[start..start + span], self.brightness);
+                }
+            }
+            if let Some((bounds, pixels)) = &self.overlay {
+                let area = rect.intersection(bounds);
+                let span = area.size.width as usize * 2;
+                let src_left = (area.top_left.x - bounds.top_left.x) as usize * 2;
+                for y in 0..area.size.height as usize {
+                    let src = ((area.top_left.y - bounds.top_left.y) as usize + y) * bounds.size.width as usize * 2 + src_left;
+                    let dst = (area.top_left.y as usize + y) * row_bytes + area.top_left.x as usize * 2;
+                    copy_dimmed(&mut fb[dst..dst + span], &pixels[src..src + span], self.brightness);
+                }
+            }
+        }
+    }
+}
+
+// rgb565 pixels, each channel scaled by brightness percent
+fn copy_dimmed(dst: &mut [u8], src: &[u8], brightness: u8) {
+    if brightness >= 100 {
+        dst.copy_from_slice(src);
+        return;
+    }
+    // out of 256 so each channel is a multiply and a shift, no dividing per pixel
+    let scale = brightness as u16 * 256 / 100;
+    for (dst, src) in dst.chunks_exact_mut(2).zip(src.chunks_exact(2)) {
+        let pixel = u16::from_le_bytes([src[0], src[1]]);
+        let high = ((pixel >> 11) * scale) >> 8;
+        let middle = (((pixel >> 5) & 0x3f) * scale) >> 8;
+        let low = ((pixel & 0x1f) * scale) >> 8;
+        dst.copy_from_slice(&((high << 11) | (middle << 5) | low).to_le_bytes());
+    }
+}
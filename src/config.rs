@@ -0,0 +1,137 @@
+use std::path::PathBuf;
+
+use crate::{dir_model::{Filter, SortKey}, utils};
+
+// plain "key = value" lines, # for comments. missing or bad keys fall back to the defaults
+const CONFIG_PATH: &str = "/home/yassin/cross_compiled/config.txt";
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub sort_key: SortKey,
+    pub filter: Filter,
+    // the navigator never goes above this
+    pub media_root: PathBuf,
+    // where usb sticks are looked for, a fake tree can be swapped in for testing
+    pub sysfs_root: PathBuf,
+    // built in (default, high_contrast, dark) or a file in the themes dir
+    pub theme: String,
+    // the carousel sliding when scrolling/changing dirs, off is a bit snappier
+    pub animations: bool,
+    // of the spi screen, in percent. the backlight's just on/off so it's done by dimming the pixels
+    pub brightness: u8,
+    // minutes w/o a press in the navigator before the backlight goes off, 0 for never
+    pub sleep_after: u64,
+    pub clock_24h: bool,
+    // anything openweathermap understands, ex. "edmonton,ca"
+    pub weather_location: String,
+    // volume change per up/down press while playing, in percent
+    pub volume_step: u64,
+}
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            sort_key: SortKey::Name,
+            filter: Filter::All,
+            media_root: PathBuf::from("/home/yassin"),
+            sysfs_root: PathBuf::from("/sys"),
+            theme: String::from("default"),
+            animations: true,
+            brightness: 100,
+            sleep_after: 0,
+            clock_24h: false,
+            weather_location: String::from("Edmonton,AB"),
+            volume_step: 5,
+        }
+    }
+}
+impl Config {
+    pub fn load() -> Config {
+        let mut config = Config::default();
+        let contents = match std::fs::read_to_string(CONFIG_PATH) {
+            Ok(contents) => contents,
+            Err(e) => {
+                println!("no config file, using defaults: {:#?}", e);
+                return config;
+            }
+        };
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                println!("bad config line: {}", line);
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "sort_key" => match SortKey::parse(value) {
+                    Some(sort_key) => config.sort_key = sort_key,
+                    None => println!("bad sort_key in config: {}", value),
+                },
+                "filter" => match Filter::parse(value) {
+                    Some(filter) => config.filter = filter,
+                    None => println!("bad filter in config: {}", value),
+                },
+                "media_root" => config.media_root = PathBuf::from(value),
+                "sysfs_root" => config.sysfs_root = PathBuf::from(value),
+                "theme" => config.theme = value.to_string(),
+                "animations" => match value.parse() {
+                    Ok(animations) => config.animations = animations,
+                    Err(_) => println!("bad animations in config: {}", value),
+                },
+                "brightness" => match value.parse::<u8>() {
+                    Ok(brightness) if brightness <= 100 => config.brightness = brightness,
+                    _ => println!("bad brightness in config: {}", value),
+                },
+                "sleep_after" => match value.parse() {
+                    Ok(sleep_after) => config.sleep_after = sleep_after,
+                    Err(_) => println!("bad sleep_after in config: {}", value),
+                },
+                "clock" => match value {
+                    "12h" => config.clock_24h = false,
+                    "24h" => config.clock_24h = true,
+                    _ => println!("bad clock in config: {}", value),
+                },
+                "weather_location" => config.weather_location = value.to_string(),
+                "volume_step" => match value.parse::<u64>() {
+                    Ok(volume_step) if (1..=100).contains(&volume_step) => config.volume_step = volume_step,
+                    _ => println!("bad volume_step in config: {}", value),
+                },
+                _ => println!("unknown config key: {}", key),
+            }
+        }
+        config
+    }
+    // written back whole when something's changed in the settings, comments in the old file are lost
+    pub fn save(&self) {
+        let contents = format!(
+            "# written by the settings menu\n\
+            sort_key = {}\n\
+            filter = {}\n\
+            media_root = {}\n\
+            sysfs_root = {}\n\
+            theme = {}\n\
+            animations = {}\n\
+            brightness = {}\n\
+            sleep_after = {}\n\
+            clock = {}\n\
+            weather_location = {}\n\
+            volume_step = {}\n",
+            self.sort_key.as_str(),
+            self.filter.as_str(),
+            self.media_root.display(),
+            self.sysfs_root.display(),
+            self.theme,
+            self.animations,
+            self.brightness,
+            self.sleep_after,
+            if self.clock_24h { "24h" } else { "12h" },
+            self.weather_location,
+            self.volume_step,
+        );
+        if let Err(e) = utils::write_atomic(CONFIG_PATH, &contents) {
+            println!("could not save config: {:#?}", e);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use std::{cmp::Ordering, os::unix::fs::MetadataExt, path::{Path, PathBuf}};
+use chrono::DateTime;
+
+use crate::{determine_icon_to_draw, thumbnails, IconKind};
+
+// what entries are ordered by inside their group (folders always come first)
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SortKey {
+    Name,
+    Size,
+    Modified,
+    Type,
+}
+impl SortKey {
+    pub fn parse(value: &str) -> Option<SortKey> {
+        match value {
+            "name" => Some(SortKey::Name),
+            "size" => Some(SortKey::Size),
+            "mtime" => Some(SortKey::Modified),
+            "type" => Some(SortKey::Type),
+            _ => None,
+        }
+    }
+    // what parse takes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Size => "size",
+            SortKey::Modified => "mtime",
+            SortKey::Type => "type",
+        }
+    }
+}
+
+// which entries make it into the listing. folders are always kept (except dot folders
+// when hiding dotfiles) so you can still get to what's inside them
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Filter {
+    All,
+    NoHidden,
+    Media,
+}
+impl Filter {
+    pub fn parse(value: &str) -> Option<Filter> {
+        match value {
+            "all" => Some(Filter::All),
+            "no_hidden" => Some(Filter::NoHidden),
+            "media" => Some(Filter::Media),
+            _ => None,
+        }
+    }
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Filter::All => "all",
+            Filter::NoHidden => "no_hidden",
+            Filter::Media => "media",
+        }
+    }
+    fn keeps(&self, entry: &Entry) -> bool {
+        let hidden = entry.name.starts_with('.');
+        match self {
+            Filter::All => true,
+            Filter::NoHidden => !hidden,
+            Filter::Media => !hidden && (entry.is_dir || thumbnails::is_playable(&entry.path)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Entry {
+    // lossy, only for display
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    pub mtime: i64,
+    pub kind: IconKind,
+}
+impl Entry {
+    pub fn from_path(path: PathBuf) -> Entry {
+        let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        // follows symlinks, unlike DirEntry::metadata
+        let (is_dir, size, mtime) = match std::fs::metadata(&path) {
+            Ok(meta) => (meta.is_dir(), meta.size(), meta.mtime()),
+            Err(_) => (false, 0, 0),
+        };
+        let kind = determine_icon_to_draw(&path, is_dir);
+        Entry { name, path, is_dir, size, mtime, kind }
+    }
+    pub fn last_modified(&self) -> String {
+        match DateTime::from_timestamp(self.mtime, 0) {
+            Some(last_modified) => last_modified.naive_local().format("%m-%d-%Y, %-I:%M%P").to_string(),
+            None => String::from("?"),
+        }
+    }
+    fn extension(&self) -> String {
+        self.path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default()
+    }
+}
+
+// one directory listing, loaded once and shared by every navigation function so
+// indexes always point at the same entries
+#[derive(Clone, Debug)]
+pub struct DirModel {
+    pub path: PathBuf,
+    pub entries: Vec<Entry>,
+    pub sort_key: SortKey,
+    pub filter: Filter,
+}
+impl DirModel {
+    // entries the filter drops never make it in, so len() and indexes only count what's shown
+    pub fn load(path: &Path, sort_key: SortKey, filter: Filter) -> std::io::Result<DirModel> {
+        let entries = std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| Entry::from_path(entry.path()))
+            .collect();
+        Ok(DirModel::from_entries(path, entries, sort_key, filter))
+    }
+    // for a listing that came out of the library index
+    pub fn from_entries(path: &Path, mut entries: Vec<Entry>, sort_key: SortKey, filter: Filter) -> DirModel {
+        entries.retain(|entry| filter.keeps(entry));
+        sort_entries(&mut entries, sort_key);
+        DirModel { path: path.to_owned(), entries, sort_key, filter }
+    }
+    // stand-in for a dir that couldn't be read, so the navigator still has something to show
+    pub fn empty(path: &Path, sort_key: SortKey, filter: Filter) -> DirModel {
+        DirModel { path: path.to_owned(), entries: Vec::new(), sort_key, filter }
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn get(&self, index: usize) -> Option<&Entry> {
+        self.entries.get(index)
+    }
+    pub fn position_of(&self, path: &Path) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.path == path)
+    }
+}
+
+fn sort_entries(entries: &mut [Entry], sort_key: SortKey) {
+    entries.sort_by(|a, b| {
+        // folders first
+        b.is_dir.cmp(&a.is_dir)
+            .then_with(|| match sort_key {
+                SortKey::Name => Ordering::Equal,
+                // biggest first
+                SortKey::Size => b.size.cmp(&a.size),
+                // newest first
+                SortKey::Modified => b.mtime.cmp(&a.mtime),
+                SortKey::Type => a.extension().cmp(&b.extension()),
+            })
+            .then_with(|| natural_cmp(&a.name, &b.name))
+    });
+}
+
+// case insensitive, and runs of digits compare by value so "ep2" < "ep10"
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    loop {
+        match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a_char), Some(b_char)) if a_char.is_ascii_digit() && b_char.is_ascii_digit() => {
+                let mut a_digits = String::new();
+                while let Some(digit) = a_chars.next_if(|c| c.is_ascii_digit()) {
+                    a_digits.push(digit);
+                }
+                let mut b_digits = String::new();
+                while let Some(digit) = b_chars.next_if(|c| c.is_ascii_digit()) {
+                    b_digits.push(digit);
+                }
+                // compare by value w/o parsing (could overflow), then by leading zeros
+                let a_trimmed = a_digits.trim_start_matches('0');
+                let b_trimmed = b_digits.trim_start_matches('0');
+                let ordering = a_trimmed.len().cmp(&b_trimmed.len())
+                    .then_with(|| a_trimmed.cmp(b_trimmed))
+                    .then_with(|| a_digits.len().cmp(&b_digits.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(a_char), Some(b_char)) => {
+                let ordering = a_char.to_lowercase().cmp(b_char.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // made up, nothing here touches the disk
+    fn entry(name: &str, is_dir: bool, size: u64, mtime: i64) -> Entry {
+        let path = Path::new("/media").join(name);
+        let kind = determine_icon_to_draw(&path, is_dir);
+        Entry { name: name.to_string(), path, is_dir, size, mtime, kind }
+    }
+    fn names(dir: &DirModel) -> Vec<&str> {
+        dir.entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn natural_order() {
+        let cases = [
+            ("ep2", "ep10", Ordering::Less),
+            ("ep10", "ep2", Ordering::Greater),
+            ("ep02", "ep2", Ordering::Greater),
+            ("ep2", "ep2", Ordering::Equal),
+            ("Apple", "banana", Ordering::Less),
+            ("apple", "Banana", Ordering::Less),
+            // same apart from case, still a stable order
+            ("Apple", "apple", Ordering::Less),
+            ("a", "a1", Ordering::Less),
+            ("file9.txt", "file10.txt", Ordering::Less),
+            ("99999999999999999999999", "100000000000000000000000", Ordering::Less),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(natural_cmp(a, b), expected, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn filters() {
+        let cases = [
+            (Filter::All, vec![".hidden", ".config", "docs", "clip.rgb565", "notes.txt"]),
+            (Filter::NoHidden, vec!["docs", "clip.rgb565", "notes.txt"]),
+            (Filter::Media, vec!["docs", "clip.rgb565"]),
+        ];
+        for (filter, expected) in cases {
+            let entries = vec![
+                entry(".hidden", false, 0, 0),
+                entry(".config", true, 0, 0),
+                entry("docs", true, 0, 0),
+                entry("clip.rgb565", false, 0, 0),
+                entry("notes.txt", false, 0, 0),
+            ];
+            let dir = DirModel::from_entries(Path::new("/media"), entries, SortKey::Type, filter);
+            let mut kept = names(&dir);
+            let mut expected = expected;
+            kept.sort();
+            expected.sort();
+            assert_eq!(kept, expected, "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn dirs_come_first() {
+        let cases = [
+            (SortKey::Name, vec!["b", "z", "a.txt", "ep2.raw", "ep10.raw"]),
+            (SortKey::Size, vec!["b", "z", "ep10.raw", "ep2.raw", "a.txt"]),
+            (SortKey::Modified, vec!["z", "b", "a.txt", "ep2.raw", "ep10.raw"]),
+            (SortKey::Type, vec!["b", "z", "ep2.raw", "ep10.raw", "a.txt"]),
+        ];
+        for (sort_key, expected) in cases {
+            let entries = vec![
+                entry("ep10.raw", false, 300, 1),
+                entry("a.txt", false, 10, 3),
+                entry("z", true, 0, 5),
+                entry("ep2.raw", false, 200, 2),
+                entry("b", true, 0, 4),
+            ];
+            let dir = DirModel::from_entries(Path::new("/media"), entries, sort_key, Filter::All);
+            assert_eq!(names(&dir), expected, "{:?}", sort_key);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::{path::{Path, PathBuf}, sync::mpsc, thread, time::Duration};
+use inotify::{Inotify, WatchDescriptor, WatchMask, Watches};
+
+use crate::ButtonEvent;
+
+// copying a folder over fires a burst of events, wait for it to settle before reloading
+const SETTLE_TIME: Duration = Duration::from_millis(300);
+
+// owned by the main loop, points the watch at whatever dir the navigator is in
+pub struct DirWatcher {
+    // None if inotify couldn't be set up, the navigator just won't live update
+    watches: Option<Watches>,
+    current: Option<(PathBuf, WatchDescriptor)>,
+}
+impl DirWatcher {
+    // no-op if already watching path
+    pub fn watch(&mut self, path: &Path) {
+        let Some(watches) = &mut self.watches else {
+            return;
+        };
+        if self.current.as_ref().is_some_and(|(current_path, _)| current_path == path) {
+            return;
+        }
+        if let Some((_, wd)) = self.current.take() {
+            // fails if the dir was deleted, the watch is already gone then
+            let _ = watches.remove(wd);
+        }
+        // close_write so a file being copied in shows its final size once it's done
+        let mask = WatchMask::CREATE | WatchMask::DELETE | WatchMask::MOVED_FROM | WatchMask::MOVED_TO | WatchMask::CLOSE_WRITE;
+        match watches.add(path, mask) {
+            Ok(wd) => self.current = Some((path.to_owned(), wd)),
+            Err(e) => println!("could not watch {}: {:#?}", path.display(), e),
+        }
+    }
+}
+
+pub fn start_dir_watch_task(tx: mpsc::Sender<ButtonEvent>) -> DirWatcher {
+    let inotify = match Inotify::init() {
+        Ok(inotify) => inotify,
+        Err(e) => {
+            println!("inotify init failed, dir won't live update: {:#?}", e);
+            return DirWatcher { watches: None, current: None };
+        }
+    };
+    let watches = inotify.watches();
+    thread::spawn(move || {
+        dir_watch_task(inotify, tx);
+    });
+    DirWatcher { watches: Some(watches), current: None }
+}
+
+// sends one DirChanged per burst of changes, the main loop reloads the listing
+fn dir_watch_task(mut inotify: Inotify, tx: mpsc::Sender<ButtonEvent>) {
+    let mut buffer = [0u8; 4096];
+    loop {
+        if let Err(e) = inotify.read_events_blocking(&mut buffer) {
+            println!("inotify read failed: {:#?}", e);
+            thread::sleep(SETTLE_TIME);
+            continue;
+        }
+        // drain whatever else came in while things settled
+        thread::sleep(SETTLE_TIME);
+        // (the fd is non-blocking outside read_events_blocking, errors w/ WouldBlock once empty)
+        while inotify.read_events(&mut buffer).is_ok() {}
+        // main loop's gone, nobody left to tell
+        if tx.send(ButtonEvent::DirChanged).is_err() {
+            break;
+        }
+    }
+}
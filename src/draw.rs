@@ -2,50 +2,19 @@ use embedded_graphics::{
   pixelcolor::{BinaryColor, Rgb565}, prelude::*, primitives::{Circle, CornerRadii, Line, Polyline, PrimitiveStyle, Rectangle, RoundedRectangle, Triangle}
 };
 // constants
-// spi tft ili 9341 lcd screen 320widthx240height
-pub const TOP_NAV_BG_COORDS: Point = Point::new(10, 10);
-pub const TOP_NAV_FILE_INDEX_COORDS: Point = Point::new(46, 18);
-pub const TOP_NAV_PATH_COORDS: Point = Point::new(20, 36);
-pub const TOP_NAV_WEATHER_TEXT_COORDS: Point = Point::new(186, 16);
-pub const TOP_NAV_CLOCK_TEXT_COORDS: Point = Point::new(244, 16);
-pub const CAROUSEL_CONTAINER_BG_COORDS:Point = Point::new(40, 90);
-pub const TOP_CAROUSEL_BG_COORDS: Point = Point::new(50, 90);
-pub const MIDDLE_CAROUSEL_BG_COORDS: Point = Point::new(50, 140);
-pub const BOTTOM_CAROUSEL_BG_COORDS: Point = Point::new(50, 190);
-
-// carousel icons
-pub const TOP_CAROUSEL_ICON_COORDS: Point = Point::new(10, 90);
-pub const MIDDLE_CAROUSEL_ICON_COORDS: Point = Point::new(10, 140);
-pub const BOTTOM_CAROUSEL_ICON_COORDS: Point = Point::new(10, 190);
-
-// file_size, last_modified text - goes between nav_bar and carousel container
-pub const ENTRY_META_FILESIZE_COORDS: Point = Point::new(40, 60);
-pub const ENTRY_META_FILESIZE_TEXT_COORDS: Point = Point::new(40, 70);
-pub const ENTRY_META_LASTMODIFIED_COORDS: Point = Point::new(120, 60);
-pub const ENTRY_META_LASTMODIFIED_TEXT_COORDS: Point = Point::new(120, 70);
-
-pub const TOP_CAROUSEL_TXT_COORDS: Point = Point::new(60, 106);
-pub const MIDDLE_CAROUSEL_TXT_COORDS: Point = Point::new(60, 156);
-pub const BOTTOM_CAROUSEL_TXT_COORDS: Point = Point::new(60, 206);
-
-// modal
-// no opt
-pub const MODAL_NO_BORDER_COORDS: Point = Point::new(110, 160);
-// yes opt
-pub const MODAL_YES_BORDER_COORDS: Point = Point::new(170, 160);
-// places to change screen:
-// scroll up, scroll down, enter_dir_or_select_response, exit_dir, draw_nav_background
-// IMPORTANT: undraws should be of whatever the last current_idx was b4 changing
-
+// spi tft ili 9341 lcd screen 320widthx240height, the main panel is laid out in nav_panel.rs
 
 // i2c screen 128x32 ssd1306 I think
+pub const I2C_WIDTH: u32 = 128;
 pub const DISPLAYSTATE_COORDS: Point = Point::new(0, 0);
 pub const TOP_MEDIA_TIMESTAMP_COORDS: Point = Point::new(0, 0);
 pub const TOP_VOLUME_VALUE_COORDS: Point = Point::new(64, 20);
+// 2nd line of the 1st small screen, under the display state
+pub const SECOND_LINE_COORDS: Point = Point::new(0, 18);
 
 use crate::FramebufferDisplay;
-pub fn draw_clock(display: &mut FramebufferDisplay, width: usize, height: usize, top_left: Point) {
-    let style = PrimitiveStyle::with_stroke(Rgb565::WHITE, 1);
+pub fn draw_clock(display: &mut FramebufferDisplay, width: usize, height: usize, top_left: Point, color: Rgb565) {
+    let style = PrimitiveStyle::with_stroke(color, 1);
 
     // Use a radius of 8 to allow 1px stroke padding around the circle
     let radius = 8;
@@ -69,9 +38,9 @@ pub fn draw_clock(display: &mut FramebufferDisplay, width: usize, height: usize,
         .draw(display)
         .unwrap();
 }
-pub fn draw_folder(display: &mut FramebufferDisplay, width: usize, height: usize, top_left: Point) {
+pub fn draw_folder(display: &mut FramebufferDisplay, width: usize, height: usize, top_left: Point, color: Rgb565) {
     // let mut display = FramebufferDisplay { buf: fb, width, height };
-    let style = PrimitiveStyle::with_stroke(Rgb565::WHITE, 1);
+    let style = PrimitiveStyle::with_stroke(color, 1);
 
     // Folder outline (with tab)
     let outline = [
@@ -90,8 +59,8 @@ pub fn draw_folder(display: &mut FramebufferDisplay, width: usize, height: usize
         .draw(display)
         .unwrap();
 }
-pub fn draw_cloud(display: &mut FramebufferDisplay, width: usize, height: usize, top_left: Point) {
-    let style = PrimitiveStyle::with_stroke(Rgb565::WHITE, 1);
+pub fn draw_cloud(display: &mut FramebufferDisplay, width: usize, height: usize, top_left: Point, color: Rgb565) {
+    let style = PrimitiveStyle::with_stroke(color, 1);
     
     let outline = [
         top_left + Point::new(6, 18),
@@ -122,9 +91,9 @@ pub fn draw_cloud(display: &mut FramebufferDisplay, width: usize, height: usize,
         .draw(display)
         .unwrap();
 }
-pub fn draw_camera(fb: &mut [u8], width: usize, height: usize, top_left: Point) {
-    let mut display = FramebufferDisplay { buf: fb, width, height };    
-    let style = PrimitiveStyle::with_stroke(Rgb565::WHITE, 1);
+// these two go in the carousel, so they take any target (it's clipped while it slides)
+pub fn draw_camera<D: DrawTarget<Color = Rgb565>>(display: &mut D, top_left: Point, color: Rgb565) where D::Error: std::fmt::Debug {
+    let style = PrimitiveStyle::with_stroke(color, 1);
     
     // 1. Camera Body (Rounded Rectangle)
     let body_top_left = top_left + Point::new(4, 6);
@@ -132,7 +101,7 @@ pub fn draw_camera(fb: &mut [u8], width: usize, height: usize, top_left: Point)
     let corner_radius = Size::new(3, 3);
     let body = RoundedRectangle::with_equal_corners(Rectangle::new(body_top_left, body_size), corner_radius);
     body.into_styled(style)
-        .draw(&mut display)
+        .draw(display)
         .unwrap();
 
     // 2. Lens (Triangle facing right)
@@ -142,7 +111,30 @@ pub fn draw_camera(fb: &mut [u8], width: usize, height: usize, top_left: Point)
         top_left + Point::new(30, 12), // Bottom-right point of the triangle
     );
     lens.into_styled(style)
-        .draw(&mut display)
+        .draw(display)
+        .unwrap();
+}
+pub fn draw_usb_stick<D: DrawTarget<Color = Rgb565>>(display: &mut D, top_left: Point, color: Rgb565) where D::Error: std::fmt::Debug {
+    let style = PrimitiveStyle::with_stroke(color, 1);
+
+    // stick body, lying on its side
+    RoundedRectangle::with_equal_corners(Rectangle::new(top_left + Point::new(2, 6), Size::new(18, 12)), Size::new(2, 2))
+        .into_styled(style)
+        .draw(display)
+        .unwrap();
+
+    // connector sticking out the right, w/ the 2 little holes
+    Rectangle::new(top_left + Point::new(20, 8), Size::new(8, 8))
+        .into_styled(style)
+        .draw(display)
+        .unwrap();
+    Rectangle::new(top_left + Point::new(22, 10), Size::new(2, 2))
+        .into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)
+        .unwrap();
+    Rectangle::new(top_left + Point::new(25, 10), Size::new(2, 2))
+        .into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)
         .unwrap();
 }
 pub fn draw_file(fb: &mut [u8], width: usize, height: usize, top_left: Point) {
@@ -0,0 +1,265 @@
+use std::{fs::File, io::prelude::*, path::{Path, PathBuf}, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, mpsc, Arc, Mutex}, thread};
+use embedded_graphics::prelude::*;
+
+use crate::{modal::Modal, theme::Role, utils, widgets::{Node, Screen, WidgetKind}, ButtonEvent, HEIGHT, WIDTH};
+
+// copy chunk, also how often progress/cancel get checked
+const COPY_CHUNK: usize = 256 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MenuAction {
+    Star,
+    Unstar,
+    Eject,
+    Rename,
+    Move,
+    Copy,
+    Paste,
+    Delete,
+    // not about the entry, it's just always in this menu
+    Settings,
+}
+impl MenuAction {
+    fn label(&self) -> &'static str {
+        match self {
+            MenuAction::Star => "Star",
+            MenuAction::Unstar => "Unstar",
+            MenuAction::Eject => "Eject",
+            MenuAction::Rename => "Rename",
+            MenuAction::Move => "Move to...",
+            MenuAction::Copy => "Copy to...",
+            MenuAction::Paste => "Paste here",
+            MenuAction::Delete => "Delete",
+            MenuAction::Settings => "Settings",
+        }
+    }
+}
+
+// long select in the navigator, a modal w/ the entry's name and one option per action
+pub struct ContextMenu {
+    // the selected entry, None in an empty dir (only paste makes sense there)
+    pub target: Option<PathBuf>,
+    pub actions: Vec<MenuAction>,
+    pub modal: Modal,
+}
+impl ContextMenu {
+    pub fn new() -> ContextMenu {
+        ContextMenu { target: None, actions: Vec::new(), modal: Modal::new("", &[]) }
+    }
+    pub fn open(&mut self, target: Option<PathBuf>, actions: Vec<MenuAction>) {
+        let title = target.as_ref()
+            .and_then(|target| target.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from("This folder"));
+        let labels: Vec<&str> = actions.iter().map(|action| action.label()).collect();
+        self.modal = Modal::new(&title, &labels);
+        self.target = target;
+        self.actions = actions;
+    }
+    pub fn selected_action(&self) -> Option<MenuAction> {
+        self.actions.get(self.modal.selected).copied()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Operation {
+    Move,
+    Copy,
+}
+
+// picked w/ "Move to..."/"Copy to...", pasted into whatever folder we browse to
+#[derive(Clone, Debug)]
+pub struct Clipboard {
+    pub path: PathBuf,
+    pub operation: Operation,
+}
+
+pub fn delete(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
+    result.map_err(|e| format!("Delete error: Could not delete {}: {}", path.display(), e))
+}
+
+pub fn rename(path: &Path, new_name: &str) -> Result<PathBuf, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name == "." || new_name == ".." || new_name.contains('/') {
+        return Err(format!("Rename error: \"{}\" is not a valid name.", new_name));
+    }
+    let new_path = path.with_file_name(new_name);
+    if new_path.exists() {
+        return Err(format!("Rename error: {} already exists.", new_name));
+    }
+    std::fs::rename(path, &new_path).map_err(|e| format!("Rename error: Could not rename {}: {}", path.display(), e))?;
+    Ok(new_path)
+}
+
+// a copy/move running in the background, progress is read by the main loop on TransferProgress
+pub struct Transfer {
+    pub clipboard: Clipboard,
+    // where it's going, the clipboard path w/ the dir it was pasted in
+    pub dest: PathBuf,
+    pub done_bytes: Arc<AtomicU64>,
+    pub total_bytes: u64,
+    pub cancel: Arc<AtomicBool>,
+    // filled in right before TransferDone is sent
+    pub result: Arc<Mutex<Option<Result<(), String>>>>,
+    // last percent drawn, so the bar only redraws when it moves
+    pub drawn_percent: u64,
+}
+impl Transfer {
+    pub fn percent(&self) -> u64 {
+        (self.done_bytes.load(Ordering::Relaxed) * 100).checked_div(self.total_bytes).unwrap_or(100)
+    }
+    pub fn take_result(&self) -> Result<(), String> {
+        self.result.lock().unwrap().take().unwrap_or(Ok(()))
+    }
+    pub fn progress(&self) -> Progress {
+        let verb = match self.clipboard.operation {
+            Operation::Move => "Moving",
+            Operation::Copy => "Copying",
+        };
+        let name = self.clipboard.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let done = format!("{} / {}", utils::format_bytes(self.done_bytes.load(Ordering::Relaxed)), utils::format_bytes(self.total_bytes));
+        Progress { title: format!("{} {}", verb, name), detail: done, percent: self.percent() }
+    }
+}
+
+// checks the paste makes sense, then kicks off the copy/move on its own thread
+pub fn start_transfer(clipboard: Clipboard, dest_dir: &Path, tx: mpsc::Sender<ButtonEvent>) -> Result<Transfer, String> {
+    let name = clipboard.path.file_name().ok_or_else(|| String::from("Paste error: Nothing to paste."))?;
+    let dest = dest_dir.join(name);
+    if dest.exists() {
+        return Err(format!("Paste error: {} already exists here.", name.to_string_lossy()));
+    }
+    if dest.starts_with(&clipboard.path) {
+        return Err(String::from("Paste error: Can't put a folder inside itself."));
+    }
+    if !clipboard.path.exists() {
+        return Err(format!("Paste error: {} is gone.", clipboard.path.display()));
+    }
+
+    // same filesystem moves are just a rename, nothing to show progress for
+    let renamed = clipboard.operation == Operation::Move && std::fs::rename(&clipboard.path, &dest).is_ok();
+    let total_bytes = if renamed { 0 } else { total_size(&clipboard.path) };
+    let transfer = Transfer {
+        clipboard: clipboard.clone(),
+        dest: dest.clone(),
+        done_bytes: Arc::new(AtomicU64::new(0)),
+        total_bytes,
+        cancel: Arc::new(AtomicBool::new(false)),
+        result: Arc::new(Mutex::new(None)),
+        drawn_percent: 0,
+    };
+    if renamed {
+        *transfer.result.lock().unwrap() = Some(Ok(()));
+        tx.send(ButtonEvent::TransferDone).unwrap();
+        return Ok(transfer);
+    }
+
+    let done_bytes = transfer.done_bytes.clone();
+    let cancel = transfer.cancel.clone();
+    let result = transfer.result.clone();
+    thread::spawn(move || {
+        let mut copy_result = copy_recursive(&clipboard.path, &dest, &done_bytes, &cancel, &tx);
+        if copy_result.is_ok() && cancel.load(Ordering::Acquire) {
+            copy_result = Err(String::from("Cancelled."));
+        }
+        match copy_result {
+            Ok(()) => {
+                // a move is a copy, then the original goes
+                if clipboard.operation == Operation::Move {
+                    copy_result = delete(&clipboard.path);
+                }
+            }
+            Err(_) => {
+                // don't leave half a copy lying around
+                let _ = delete(&dest);
+            }
+        }
+        *result.lock().unwrap() = Some(copy_result);
+        let _ = tx.send(ButtonEvent::TransferDone);
+    });
+    Ok(transfer)
+}
+
+// symlinks aren't followed here or in copy_recursive, they're recreated as links so they count for nothing
+fn total_size(path: &Path) -> u64 {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::read_dir(path).into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| total_size(&entry.path()))
+            .sum(),
+        Ok(meta) if meta.is_symlink() => 0,
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    }
+}
+
+fn copy_recursive(src: &Path, dest: &Path, done_bytes: &AtomicU64, cancel: &AtomicBool, tx: &mpsc::Sender<ButtonEvent>) -> Result<(), String> {
+    if cancel.load(Ordering::Acquire) {
+        return Ok(());
+    }
+    let file_type = std::fs::symlink_metadata(src).map_err(|e| format!("Copy error: Could not read {}: {}", src.display(), e))?.file_type();
+    if file_type.is_symlink() {
+        // pointing at the same thing as before, relative links stay relative
+        let target = std::fs::read_link(src).map_err(|e| format!("Copy error: Could not read link {}: {}", src.display(), e))?;
+        std::os::unix::fs::symlink(&target, dest).map_err(|e| format!("Copy error: Could not create link {}: {}", dest.display(), e))?;
+        return Ok(());
+    }
+    if file_type.is_dir() {
+        std::fs::create_dir(dest).map_err(|e| format!("Copy error: Could not create {}: {}", dest.display(), e))?;
+        let entries = std::fs::read_dir(src).map_err(|e| format!("Copy error: Could not read {}: {}", src.display(), e))?;
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            copy_recursive(&entry.path(), &dest.join(entry.file_name()), done_bytes, cancel, tx)?;
+        }
+        return Ok(());
+    }
+
+    let mut src_file = File::open(src).map_err(|e| format!("Copy error: Could not open {}: {}", src.display(), e))?;
+    let mut dest_file = File::create(dest).map_err(|e| format!("Copy error: Could not create {}: {}", dest.display(), e))?;
+    let mut chunk = vec![0u8; COPY_CHUNK];
+    loop {
+        if cancel.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let read = src_file.read(&mut chunk).map_err(|e| format!("Copy error: Could not read {}: {}", src.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        dest_file.write_all(&chunk[..read]).map_err(|e| format!("Copy error: Could not write {}: {}", dest.display(), e))?;
+        done_bytes.fetch_add(read as u64, Ordering::Relaxed);
+        // nobody's waiting on it anymore, same as a cancel
+        if tx.send(ButtonEvent::TransferProgress).is_err() {
+            return Err(String::from("Cancelled."));
+        }
+    }
+    Ok(())
+}
+
+// full screen w/ a title, a bar and a line of detail under it
+#[derive(Clone, Debug)]
+pub struct Progress {
+    pub title: String,
+    pub detail: String,
+    pub percent: u64,
+}
+impl Progress {
+    pub fn screen(&self) -> Screen {
+        let mut screen = Screen::new(Node::Stack(vec![
+            (Point::new(30, 85), Node::Column { spacing: 4, children: vec![
+                Node::widget("title", WidgetKind::label(""), 260, 20),
+                Node::widget("bar", WidgetKind::Progress { percent: 0 }, 260, 16),
+                Node::widget("detail", WidgetKind::label(""), 260, 14),
+                Node::widget("hint", WidgetKind::Label { text: String::from("Escape to cancel"), color: Role::Muted }, 260, 14),
+            ]}),
+        ]), WIDTH, HEIGHT);
+        self.update(&mut screen);
+        screen
+    }
+    // only the bar and the numbers move while it's going
+    pub fn update(&self, screen: &mut Screen) {
+        screen.set("title", WidgetKind::label(&self.title));
+        screen.set("bar", WidgetKind::Progress { percent: self.percent });
+        screen.set("detail", WidgetKind::label(&format!("{}%  {}", self.percent.min(100), self.detail)));
+    }
+}
@@ -0,0 +1,222 @@
+use embedded_graphics::{mono_font::{iso_8859_1, iso_8859_5, iso_8859_7, MonoFont, MonoTextStyle}, prelude::*, primitives::{PrimitiveStyle, Rectangle}, text::{Baseline, Text}};
+
+// everything the mono fonts don't have (cjk mostly), built in from gnu unifont by build.rs
+include!(concat!(env!("OUT_DIR"), "/wide_glyphs.rs"));
+const WIDE_GLYPH_HEIGHT: u32 = 16;
+// plain dots, '…' isn't in the mono fonts
+const ELLIPSIS: &str = "...";
+
+// 8 or 16 wide, one bit per pixel, msb first, rows padded to whole bytes
+struct WideGlyph {
+    width: u32,
+    bits: &'static [u8],
+}
+
+// latin-1, cyrillic and greek versions of a font size, tried in that order
+fn family(font: &MonoFont) -> [&'static MonoFont<'static>; 3] {
+    match (font.character_size.width, font.character_size.height) {
+        (5, 8) => [&iso_8859_1::FONT_5X8, &iso_8859_5::FONT_5X8, &iso_8859_7::FONT_5X8],
+        (6, 13) => [&iso_8859_1::FONT_6X13, &iso_8859_5::FONT_6X13, &iso_8859_7::FONT_6X13],
+        (7, 13) => [&iso_8859_1::FONT_7X13, &iso_8859_5::FONT_7X13, &iso_8859_7::FONT_7X13],
+        (8, 13) => [&iso_8859_1::FONT_8X13, &iso_8859_5::FONT_8X13, &iso_8859_7::FONT_8X13],
+        _ => [&iso_8859_1::FONT_6X10, &iso_8859_5::FONT_6X10, &iso_8859_7::FONT_6X10],
+    }
+}
+
+// missing chars map to the same glyph as '?' does
+fn has_glyph(font: &MonoFont, c: char) -> bool {
+    c == '?' || font.glyph_mapping.index(c) != font.glyph_mapping.index('?')
+}
+
+enum Glyph {
+    Mono(&'static MonoFont<'static>),
+    Wide(WideGlyph),
+    Missing,
+}
+
+fn glyph(font: &MonoFont, c: char) -> Glyph {
+    if let Some(mono) = family(font).into_iter().find(|mono| has_glyph(mono, c)) {
+        return Glyph::Mono(mono);
+    }
+    match wide_glyph(c) {
+        Some(wide) => Glyph::Wide(wide),
+        None => Glyph::Missing,
+    }
+}
+
+// false if it'd be drawn as a box
+pub fn can_draw(c: char, font: &MonoFont) -> bool {
+    !matches!(glyph(font, c), Glyph::Missing)
+}
+
+fn advance(font: &MonoFont, glyph: &Glyph) -> u32 {
+    match glyph {
+        Glyph::Wide(wide) => wide.width + font.character_spacing,
+        _ => font.character_size.width + font.character_spacing,
+    }
+}
+
+// widest line, in pixels
+pub fn text_width(text: &str, font: &MonoFont) -> u32 {
+    text.lines()
+        .map(|line| line.chars().map(|c| advance(font, &glyph(font, c))).sum())
+        .max()
+        .unwrap_or(0)
+}
+
+// cut down to max_width w/ "..." on the end, each line on its own. untouched if it already fits
+pub fn ellipsize(text: &str, max_width: u32, font: &MonoFont) -> String {
+    let ellipsis_width = text_width(ELLIPSIS, font);
+    let lines: Vec<String> = text.lines()
+        .map(|line| {
+            if text_width(line, font) <= max_width {
+                return line.to_string();
+            }
+            let mut width = 0;
+            let mut cut: String = line.chars()
+                .take_while(|&c| {
+                    width += advance(font, &glyph(font, c));
+                    width + ellipsis_width <= max_width
+                })
+                .collect();
+            cut.push_str(ELLIPSIS);
+            cut
+        })
+        .collect();
+    lines.join("\n")
+}
+
+// broken into lines that fit max_width, on spaces where it can and mid word when a word is too long on its own.
+// line breaks already in it are kept
+pub fn wrap(text: &str, max_width: u32, font: &MonoFont) -> Vec<String> {
+    let space_width = text_width(" ", font);
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        // kept as it goes, whole text readers go through here
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split(' ') {
+            let word_width = text_width(word, font);
+            let joined_width = if line.is_empty() { word_width } else { line_width + space_width + word_width };
+            if joined_width <= max_width {
+                if !line.is_empty() {
+                    line.push(' ');
+                }
+                line.push_str(word);
+                line_width = joined_width;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            for c in word.chars() {
+                let c_width = advance(font, &glyph(font, c));
+                if !line.is_empty() && line_width + c_width > max_width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                line.push(c);
+                line_width += c_width;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// like Text w/ Baseline::Top, except any char one of the fonts has gets drawn and anything else is a box
+pub fn draw_text<D: DrawTarget>(display: &mut D, text: &str, top_left: Point, font: &MonoFont, color: D::Color) where D::Error: std::fmt::Debug {
+    let line_height = font.character_size.height as i32;
+    let mut point = top_left;
+    let mut encoded = [0u8; 4];
+    for c in text.chars() {
+        if c == '\n' {
+            point = Point::new(top_left.x, point.y + line_height);
+            continue;
+        }
+        let glyph = glyph(font, c);
+        match &glyph {
+            Glyph::Mono(mono) => {
+                Text::with_baseline(c.encode_utf8(&mut encoded), point, MonoTextStyle::new(mono, color), Baseline::Top)
+                    .draw(display)
+                    .unwrap();
+            }
+            Glyph::Wide(wide) => {
+                // taller than the line, so centered on it
+                let top = point + Point::new(0, (line_height - WIDE_GLYPH_HEIGHT as i32) / 2);
+                let row_bytes = wide.width.div_ceil(8) as usize;
+                let pixels = (0..WIDE_GLYPH_HEIGHT).flat_map(|y| (0..wide.width).map(move |x| (x, y)))
+                    .filter(|&(x, y)| wide.bits[y as usize * row_bytes + x as usize / 8] & (0x80 >> (x % 8)) != 0)
+                    .map(|(x, y)| Pixel(top + Point::new(x as i32, y as i32), color));
+                display.draw_iter(pixels).unwrap();
+            }
+            Glyph::Missing => {
+                Rectangle::new(point + Point::new(0, 1), font.character_size.saturating_sub(Size::new(1, 2)))
+                    .into_styled(PrimitiveStyle::with_stroke(color, 1))
+                    .draw(display)
+                    .unwrap();
+            }
+        }
+        point.x += advance(font, &glyph) as i32;
+    }
+}
+
+fn wide_glyph(c: char) -> Option<WideGlyph> {
+    let idx = WIDE_GLYPHS.binary_search_by_key(&(c as u32), |&(codepoint, _, _)| codepoint).ok()?;
+    let (_, width, offset) = WIDE_GLYPHS[idx];
+    let len = width as usize / 8 * WIDE_GLYPH_HEIGHT as usize;
+    Some(WideGlyph { width: width as u32, bits: &WIDE_BITS[offset as usize..offset as usize + len] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mono_font::ascii::FONT_6X10;
+
+    // the wide table is empty w/o fonts/unifont.hex, then cjk is box sized. widths are measured so both pass
+    #[test]
+    fn wide_glyph_widths() {
+        let expected = if wide_glyph('中').is_some() { 16 } else { 6 };
+        assert_eq!(text_width("中", &FONT_6X10), expected);
+        assert_eq!(text_width("ab中", &FONT_6X10), 12 + expected);
+        assert_eq!(text_width("ab\n中中", &FONT_6X10), (2 * expected).max(12));
+    }
+
+    #[test]
+    fn ellipsizes() {
+        let font = &FONT_6X10;
+        let two_wide = text_width("中文", font);
+        let cases = [
+            ("short", 60, "short"),
+            ("hello world", 42, "hell..."),
+            ("hello world", 66, "hello world"),
+            ("one line\nand another one", 48, "one line\nand a..."),
+            ("中文字幕中文字幕", two_wide + text_width(ELLIPSIS, font), "中文..."),
+            ("中文字幕", text_width("中文字幕", font), "中文字幕"),
+            ("ab中文字幕中文字幕", text_width("ab中", font) + text_width(ELLIPSIS, font), "ab中..."),
+        ];
+        for (text, max_width, expected) in cases {
+            assert_eq!(ellipsize(text, max_width, font), expected, "{:?} in {}px", text, max_width);
+        }
+    }
+
+    #[test]
+    fn wraps() {
+        let font = &FONT_6X10;
+        let cases = [
+            ("the quick brown fox", 60, vec!["the quick", "brown fox"]),
+            ("a\nb c", 60, vec!["a", "b c"]),
+            ("abcdefghij", 24, vec!["abcd", "efgh", "ij"]),
+            ("", 24, vec![]),
+            ("中文 字幕", text_width("中文", font), vec!["中文", "字幕"]),
+            // too long on its own, split between glyphs
+            ("中文字幕", text_width("中文字", font) - 1, vec!["中文", "字幕"]),
+            // a word that fits on a line of its own isn't split
+            ("ab 中文", text_width("ab 中", font), vec!["ab", "中文"]),
+        ];
+        for (text, max_width, expected) in cases {
+            assert_eq!(wrap(text, max_width, font), expected, "{:?} in {}px", text, max_width);
+        }
+    }
+}
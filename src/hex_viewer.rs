@@ -0,0 +1,162 @@
+use std::{fs::File, io::prelude::*, path::{Path, PathBuf}};
+use embedded_graphics::{mono_font::{ascii::FONT_6X10, MonoTextStyle}, prelude::*, text::{Baseline, Text}};
+
+use crate::{clear_screen, text_reader::{ScrollStep, TEXT_COLS, TEXT_ROWS}, theme::Theme, utils, FramebufferDisplay, HEIGHT, WIDTH};
+
+// "offset  hex bytes  ascii", as many bytes per line as fit in the text grid:
+// 8 offset + 2 gap + 3 per byte (minus trailing space) + 2 gap + 1 per byte
+pub const BYTES_PER_LINE: usize = (TEXT_COLS - 11) / 4;
+const TEXT_MARGIN: i32 = 4;
+
+pub struct HexViewerData {
+    pub path: PathBuf,
+    pub file_size: u64,
+    top_line: u64,
+    pub step: ScrollStep,
+    // short description of the file, ex. "PNG image" or "rgb565 12 frames"
+    pub magic: String,
+}
+impl HexViewerData {
+    pub fn new() -> HexViewerData {
+        HexViewerData { path: PathBuf::new(), file_size: 0, top_line: 0, step: ScrollStep::Line, magic: String::new() }
+    }
+    pub fn open(&mut self, path: &Path) -> Result<(), String> {
+        let mut file = File::open(path).map_err(|e| format!("Hex error: Could not open {}: {}", path.display(), e))?;
+        self.file_size = file.metadata().map_err(|e| format!("Hex error: Could not read {}: {}", path.display(), e))?.len();
+        let mut header = [0u8; 16];
+        let header_len = file.read(&mut header).unwrap_or(0);
+        self.magic = detect_magic(path, &header[..header_len], self.file_size);
+        self.path = path.to_owned();
+        self.top_line = 0;
+        self.step = ScrollStep::Line;
+        Ok(())
+    }
+    // for the small screen, ex. "Hex 12KB"
+    pub fn title(&self) -> String {
+        format!("Hex {}", utils::format_bytes(self.file_size))
+    }
+    fn total_lines(&self) -> u64 {
+        self.file_size.div_ceil(BYTES_PER_LINE as u64)
+    }
+    fn last_top_line(&self) -> u64 {
+        self.total_lines().saturating_sub(TEXT_ROWS as u64)
+    }
+    // false if already at the top/bottom, nothing to redraw
+    pub fn scroll(&mut self, down: bool) -> bool {
+        let step_lines = match self.step {
+            ScrollStep::Line => 1,
+            ScrollStep::Page => TEXT_ROWS as u64,
+        };
+        let new_top_line = if down {
+            (self.top_line + step_lines).min(self.last_top_line())
+        }
+        else {
+            self.top_line.saturating_sub(step_lines)
+        };
+        let changed = new_top_line != self.top_line;
+        self.top_line = new_top_line;
+        changed
+    }
+    pub fn toggle_step(&mut self) {
+        self.step = match self.step {
+            ScrollStep::Line => ScrollStep::Page,
+            ScrollStep::Page => ScrollStep::Line,
+        };
+    }
+    // only the visible page is read, files can be way bigger than ram
+    pub fn render(&self, theme: &Theme) -> Result<Vec<u8>, String> {
+        let mut page = vec![0u8; BYTES_PER_LINE * TEXT_ROWS];
+        let mut file = File::open(&self.path).map_err(|e| format!("Hex error: Could not open {}: {}", self.path.display(), e))?;
+        let start = self.top_line * BYTES_PER_LINE as u64;
+        file.seek(std::io::SeekFrom::Start(start)).map_err(|e| format!("Hex error: Could not seek: {}", e))?;
+        let mut page_len = 0;
+        while page_len < page.len() {
+            match file.read(&mut page[page_len..]) {
+                Ok(0) => break,
+                Ok(read) => page_len += read,
+                Err(e) => return Err(format!("Hex error: Could not read: {}", e)),
+            }
+        }
+
+        let mut frame = vec![0u8; WIDTH * HEIGHT * 2];
+        clear_screen(&mut frame, theme);
+        let mut display = FramebufferDisplay { buf: &mut frame, width: WIDTH, height: HEIGHT };
+        let offset_style = MonoTextStyle::new(&FONT_6X10, theme.accent);
+        let txt_style = MonoTextStyle::new(&FONT_6X10, theme.text);
+        for (row, bytes) in page[..page_len].chunks(BYTES_PER_LINE).enumerate() {
+            let y = TEXT_MARGIN + row as i32 * 10;
+            let offset = format!("{:08x}", start + (row * BYTES_PER_LINE) as u64);
+            Text::with_baseline(&offset, Point::new(TEXT_MARGIN, y), offset_style, Baseline::Top)
+                .draw(&mut display)
+                .unwrap();
+            Text::with_baseline(&format_line(bytes), Point::new(TEXT_MARGIN + 10 * 6, y), txt_style, Baseline::Top)
+                .draw(&mut display)
+                .unwrap();
+        }
+        Ok(frame)
+    }
+}
+
+// hex bytes padded to a full line, then the printable ascii
+fn format_line(bytes: &[u8]) -> String {
+    let hex: Vec<String> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    let ascii: String = bytes.iter().map(|&byte| if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' }).collect();
+    format!("{:<width$}  {}", hex.join(" "), ascii, width = BYTES_PER_LINE * 3 - 1)
+}
+
+// kept short enough for the 128px small screen
+pub fn detect_magic(path: &Path, header: &[u8], file_size: u64) -> String {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    if matches!(extension, "rgb565" | "raw" | "rgb") {
+        // exports should be a whole number of 320x240 frames, anything left over means it's broken
+        let frame_bytes = (WIDTH * HEIGHT * 2) as u64;
+        let frames = file_size / frame_bytes;
+        let leftover = file_size % frame_bytes;
+        return if leftover == 0 { format!("{} {}f ok", extension, frames) } else { format!("{}f +{}B bad", frames, leftover) };
+    }
+    match header {
+        [0x89, b'P', b'N', b'G', ..] => "PNG image",
+        [0xFF, 0xD8, 0xFF, ..] => "JPEG image",
+        [b'B', b'M', ..] => "BMP image",
+        [b'G', b'I', b'F', b'8', ..] => "GIF image",
+        [0x7F, b'E', b'L', b'F', ..] => "ELF binary",
+        [b'P', b'K', 0x03, 0x04, ..] => "ZIP archive",
+        [0x1F, 0x8B, ..] => "gzip",
+        [b'%', b'P', b'D', b'F', ..] => "PDF",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => "WAV audio",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'A', b'V', b'I', b' ', ..] => "AVI video",
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => "MP4 video",
+        [b'I', b'D', b'3', ..] => "MP3 audio",
+        [b'#', b'!', ..] => "script",
+        [] => "empty",
+        _ if header.iter().all(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace()) => "text",
+        _ => "unknown",
+    }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_magic() {
+        let cases: [(&str, &[u8], u64, &str); 12] = [
+            ("a.png", &[0x89, b'P', b'N', b'G', 0x0D, 0x0A], 100, "PNG image"),
+            ("a.jpg", &[0xFF, 0xD8, 0xFF, 0xE0], 100, "JPEG image"),
+            ("a.wav", b"RIFF\0\0\0\0WAVEfmt ", 100, "WAV audio"),
+            ("a.avi", b"RIFF\0\0\0\0AVI LIST", 100, "AVI video"),
+            ("a.mp4", b"\0\0\0\x18ftypmp42", 100, "MP4 video"),
+            ("a.sh", b"#!/bin/sh\n", 10, "script"),
+            ("notes", b"hello there\n", 12, "text"),
+            ("blob", &[0x00, 0x01, 0x02], 3, "unknown"),
+            ("empty", &[], 0, "empty"),
+            // the extension wins over whatever the header looks like
+            ("a.png.rgb565", &[0x89, b'P', b'N', b'G'], (WIDTH * HEIGHT * 2 * 3) as u64, "rgb565 3f ok"),
+            ("a.raw", &[], 0, "raw 0f ok"),
+            ("a.rgb", &[0; 16], (WIDTH * HEIGHT * 2 * 2 + 7) as u64, "2f +7B bad"),
+        ];
+        for (name, header, file_size, expected) in cases {
+            assert_eq!(detect_magic(Path::new(name), header, file_size), expected, "{}", name);
+        }
+    }
+}
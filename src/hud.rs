@@ -0,0 +1,200 @@
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle}, pixelcolor::Rgb565, prelude::*, primitives::{PrimitiveStyleBuilder, Rectangle, Triangle}, text::{Alignment, Baseline, Text, TextStyleBuilder}
+};
+
+use crate::{theme::Theme, utils, PlayingSomethingData, PIXELS_PER_FRAME, SCREEN_FPS};
+
+// hud sits on the bottom of the frame while playing, shown on any btn press
+pub const HUD_BG_COORDS: Point = Point::new(0, 196);
+pub const HUD_STATE_ICON_COORDS: Point = Point::new(10, 202);
+pub const HUD_ELAPSED_TEXT_COORDS: Point = Point::new(30, 204);
+pub const HUD_REMAINING_TEXT_COORDS: Point = Point::new(310, 204);
+pub const HUD_VOLUME_TEXT_COORDS: Point = Point::new(160, 204);
+pub const HUD_PROGRESS_BAR_COORDS: Point = Point::new(10, 222);
+
+// how often the draw task wakes up to fade the hud when no frames are coming in
+pub const HUD_TICK: Duration = Duration::from_millis(50);
+// fully visible for this long, then fades out over HUD_FADE_FOR
+const HUD_VISIBLE_FOR: Duration = Duration::from_millis(2500);
+const HUD_FADE_FOR: Duration = Duration::from_millis(500);
+// how dark the panel behind the hud gets at full opacity (out of 255)
+const HUD_BG_ALPHA: u32 = 160;
+
+pub struct Hud {
+    // atomics are shared w/ the video thread, so the hud follows playback while it's up
+    video_state: PlayingSomethingData,
+    shown_at: Instant,
+}
+impl Hud {
+    pub fn new(video_state: PlayingSomethingData) -> Hud {
+        Hud { video_state, shown_at: Instant::now() }
+    }
+    // None once the hud has completely faded out
+    pub fn opacity(&self) -> Option<u8> {
+        let elapsed = self.shown_at.elapsed();
+        if elapsed < HUD_VISIBLE_FOR {
+            Some(255)
+        }
+        else if elapsed < HUD_VISIBLE_FOR + HUD_FADE_FOR {
+            let fading_for = (elapsed - HUD_VISIBLE_FOR).as_millis() as u32;
+            Some((255 - fading_for * 255 / HUD_FADE_FOR.as_millis() as u32) as u8)
+        }
+        else {
+            None
+        }
+    }
+}
+
+// draws the hud if it's still visible, drops it once it's faded out
+pub fn draw_active_hud(fb: &mut [u8], width: usize, height: usize, active_hud: &mut Option<Hud>, theme: &Theme) {
+    if let Some(hud) = active_hud {
+        match hud.opacity() {
+            Some(opacity) => draw_hud(fb, width, height, hud, opacity, theme),
+            None => *active_hud = None,
+        }
+    }
+}
+
+// drawtarget that mixes whatever it draws with the pixels already in the framebuffer,
+// so the hud can be composited over a frame without touching the frame itself
+struct BlendedDisplay<'a> {
+    buf: &'a mut [u8],
+    width: usize,
+    height: usize,
+    alpha: u32,
+}
+impl<'a> BlendedDisplay<'a> {
+    fn read_pixel(&self, idx: usize) -> Rgb565 {
+        // undo the rgb => bgr + byte swap done by FramebufferDisplay
+        let swapped = u16::from_le_bytes([self.buf[idx], self.buf[idx + 1]]);
+        let red = swapped & 0x1F;
+        let green = (swapped >> 5) & 0x3F;
+        let blue = swapped >> 11;
+        Rgb565::new(red as u8, green as u8, blue as u8)
+    }
+    fn write_pixel(&mut self, idx: usize, color: Rgb565) {
+        let value = color.into_storage();
+        let red = (value >> 11) & 0x1F;
+        let green = (value >> 5) & 0x3F;
+        let blue = value & 0x1F;
+        let swapped = (blue << 11) | (green << 5) | red;
+        let bgr = swapped.to_le_bytes();
+        self.buf[idx] = bgr[0];
+        self.buf[idx + 1] = bgr[1];
+    }
+}
+impl<'a> OriginDimensions for BlendedDisplay<'a> {
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+impl<'a> DrawTarget for BlendedDisplay<'a> {
+    type Color = Rgb565;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+        I: IntoIterator<Item = Pixel<Self::Color>>
+    {
+        for Pixel(coord, color) in pixels {
+            if coord.x >= 0 && coord.x < self.width as i32 && coord.y >= 0 && coord.y < self.height as i32 {
+                let idx = (coord.y as usize * self.width + coord.x as usize) * 2;
+                let under = self.read_pixel(idx);
+                let mix = |over: u8, under: u8| ((over as u32 * self.alpha + under as u32 * (255 - self.alpha)) / 255) as u8;
+                let blended = Rgb565::new(mix(color.r(), under.r()), mix(color.g(), under.g()), mix(color.b(), under.b()));
+                self.write_pixel(idx, blended);
+            }
+        }
+        Ok(())
+    }
+}
+
+// progress bar, elapsed/remaining, paused icon and volume, drawn over whatever is in fb
+pub fn draw_hud(fb: &mut [u8], width: usize, height: usize, hud: &Hud, opacity: u8, theme: &Theme) {
+    let current_frame = hud.video_state.current_frame.load(Ordering::Relaxed);
+    let total_frames = hud.video_state.total_frames.load(Ordering::Relaxed) / PIXELS_PER_FRAME as u64;
+    let paused = hud.video_state.paused.load(Ordering::Acquire);
+    let volume = hud.video_state.volume.load(Ordering::Relaxed);
+
+    // darken the bottom of the frame so the hud is readable on bright video
+    let mut display = BlendedDisplay { buf: fb, width, height, alpha: HUD_BG_ALPHA * opacity as u32 / 255 };
+    let bg_style = PrimitiveStyleBuilder::new()
+        .fill_color(Rgb565::BLACK)
+        .build();
+    Rectangle::new(HUD_BG_COORDS, Size::new(width as u32, height as u32 - HUD_BG_COORDS.y as u32))
+        .into_styled(bg_style)
+        .draw(&mut display)
+        .unwrap();
+
+    let mut display = BlendedDisplay { buf: display.buf, width, height, alpha: opacity as u32 };
+    let icon_style = PrimitiveStyleBuilder::new()
+        .fill_color(theme.text)
+        .build();
+    if paused {
+        // pause bars
+        Rectangle::new(HUD_STATE_ICON_COORDS + Point::new(2, 1), Size::new(4, 12))
+            .into_styled(icon_style)
+            .draw(&mut display)
+            .unwrap();
+        Rectangle::new(HUD_STATE_ICON_COORDS + Point::new(9, 1), Size::new(4, 12))
+            .into_styled(icon_style)
+            .draw(&mut display)
+            .unwrap();
+    }
+    else {
+        // play triangle
+        Triangle::new(
+            HUD_STATE_ICON_COORDS + Point::new(2, 1),
+            HUD_STATE_ICON_COORDS + Point::new(2, 13),
+            HUD_STATE_ICON_COORDS + Point::new(13, 7),
+        )
+        .into_styled(icon_style)
+        .draw(&mut display)
+        .unwrap();
+    }
+
+    // elapsed on the left, remaining on the right, volume in the middle
+    let txt_style = MonoTextStyle::new(&FONT_6X10, theme.text);
+    let current_seconds = current_frame / SCREEN_FPS as u64;
+    let total_seconds = total_frames / SCREEN_FPS as u64;
+    Text::with_baseline(&utils::format_duration(current_seconds), HUD_ELAPSED_TEXT_COORDS, txt_style, Baseline::Top)
+        .draw(&mut display)
+        .unwrap();
+    let right_aligned = TextStyleBuilder::new()
+        .alignment(Alignment::Right)
+        .baseline(Baseline::Top)
+        .build();
+    Text::with_text_style(&format!("-{}", utils::format_duration(total_seconds.saturating_sub(current_seconds))), HUD_REMAINING_TEXT_COORDS, txt_style, right_aligned)
+        .draw(&mut display)
+        .unwrap();
+    let centered = TextStyleBuilder::new()
+        .alignment(Alignment::Center)
+        .baseline(Baseline::Top)
+        .build();
+    Text::with_text_style(&format!("Vol {}%", volume), HUD_VOLUME_TEXT_COORDS, txt_style, centered)
+        .draw(&mut display)
+        .unwrap();
+
+    // progress bar, outline then filled portion
+    let bar_size = Size::new(width as u32 - 2 * HUD_PROGRESS_BAR_COORDS.x as u32, 8);
+    let outline_style = PrimitiveStyleBuilder::new()
+        .stroke_width(1)
+        .stroke_color(theme.border)
+        .build();
+    Rectangle::new(HUD_PROGRESS_BAR_COORDS, bar_size)
+        .into_styled(outline_style)
+        .draw(&mut display)
+        .unwrap();
+    if let Some(filled_width) = ((bar_size.width - 4) as u64 * current_frame.min(total_frames)).checked_div(total_frames) {
+        let filled_width = filled_width as u32;
+        let filled_style = PrimitiveStyleBuilder::new()
+            .fill_color(theme.accent)
+            .build();
+        Rectangle::new(HUD_PROGRESS_BAR_COORDS + Point::new(2, 2), Size::new(filled_width, bar_size.height - 4))
+            .into_styled(filled_style)
+            .draw(&mut display)
+            .unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+use std::{path::{Path, PathBuf}, sync::{atomic::{AtomicBool, Ordering}, mpsc, Arc, Mutex}, thread, time::Duration};
+use embedded_graphics::{pixelcolor::Rgb565, prelude::*};
+use image::RgbImage;
+
+use crate::{clear_screen, dir_model::DirModel, fonts, theme::Theme, ButtonEvent, FramebufferDisplay, HEIGHT, WIDTH};
+
+const SLIDESHOW_INTERVAL: Duration = Duration::from_secs(5);
+const LOADING_TEXT: &str = "Loading...";
+
+pub fn is_image(path: &Path) -> bool {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => matches!(ext.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "bmp"),
+        None => false,
+    }
+}
+
+// select cycles through these, in this order
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ViewMode {
+    Fit,
+    Zoom(u32),
+    Slideshow,
+}
+impl ViewMode {
+    pub fn label(&self) -> String {
+        match self {
+            ViewMode::Fit => "Fit".to_string(),
+            ViewMode::Zoom(zoom) => format!("Zoom {}x", zoom),
+            ViewMode::Slideshow => "Slideshow".to_string(),
+        }
+    }
+}
+
+pub struct ImageViewerData {
+    // every image in the folder of the one that was opened, for next/prev and slideshow
+    pub images: Vec<PathBuf>,
+    pub current: usize,
+    pub mode: ViewMode,
+    // which screen-sized tile of the zoomed image is shown, left to right then top to bottom
+    pan_index: usize,
+    decoded: Option<RgbImage>,
+    // waiting on the decoder, the screen says loading until ImageDecoded comes in
+    pub loading: bool,
+    decoder: Decoder,
+    // read by slideshow_task
+    pub slideshow: Arc<AtomicBool>,
+}
+impl ImageViewerData {
+    pub fn new(tx: mpsc::Sender<ButtonEvent>) -> ImageViewerData {
+        ImageViewerData { images: Vec::new(), current: 0, mode: ViewMode::Fit, pan_index: 0, decoded: None, loading: false, decoder: start_decode_task(tx), slideshow: Arc::new(AtomicBool::new(false)) }
+    }
+    // dir is the navigator's listing, so next/prev follow the same order as the carousel
+    pub fn open(&mut self, path: &Path, dir: &DirModel) -> Result<(), String> {
+        let images: Vec<PathBuf> = dir.entries.iter()
+            .filter(|entry| !entry.is_dir && is_image(&entry.path))
+            .map(|entry| entry.path.clone())
+            .collect();
+        self.current = images.iter().position(|image| image == path).unwrap_or(0);
+        self.images = images;
+        self.set_mode(ViewMode::Fit);
+        self.decode()
+    }
+    pub fn close(&mut self) {
+        self.set_mode(ViewMode::Fit);
+        self.decoded = None;
+        self.loading = false;
+    }
+    pub fn current_name(&self) -> String {
+        self.images.get(self.current)
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+    // hands the current image to the decoder, render shows LOADING_TEXT until it's done
+    fn decode(&mut self) -> Result<(), String> {
+        let path = self.images.get(self.current).ok_or_else(|| String::from("Image error: There are no images in this folder."))?;
+        self.decoder.tx.send(path.clone()).unwrap();
+        self.loading = true;
+        Ok(())
+    }
+    // on ImageDecoded. None if it was for an image we've since moved on from
+    pub fn take_decoded(&mut self) -> Option<Result<(), String>> {
+        let (path, decoded) = self.decoder.decoded.lock().unwrap().take()?;
+        if !self.loading || self.images.get(self.current) != Some(&path) {
+            return None;
+        }
+        self.loading = false;
+        Some(decoded.map(|image| {
+            self.decoded = Some(image);
+            self.pan_index = 0;
+        }))
+    }
+    pub fn next_image(&mut self) -> Result<(), String> {
+        if self.images.is_empty() {
+            return Ok(());
+        }
+        self.current = (self.current + 1) % self.images.len();
+        self.decode()
+    }
+    pub fn prev_image(&mut self) -> Result<(), String> {
+        if self.images.is_empty() {
+            return Ok(());
+        }
+        self.current = (self.current + self.images.len() - 1) % self.images.len();
+        self.decode()
+    }
+    pub fn cycle_mode(&mut self) {
+        let next_mode = match self.mode {
+            ViewMode::Fit => ViewMode::Zoom(2),
+            ViewMode::Zoom(2) => ViewMode::Zoom(4),
+            ViewMode::Zoom(_) => ViewMode::Slideshow,
+            ViewMode::Slideshow => ViewMode::Fit,
+        };
+        self.set_mode(next_mode);
+    }
+    fn set_mode(&mut self, mode: ViewMode) {
+        self.mode = mode;
+        self.pan_index = 0;
+        self.slideshow.store(mode == ViewMode::Slideshow, Ordering::Release);
+    }
+    // up/down: pans while zoomed, otherwise flips through the folder
+    pub fn step(&mut self, forward: bool) -> Result<(), String> {
+        match self.mode {
+            ViewMode::Zoom(_) => {
+                let (cols, rows) = self.tile_grid();
+                let tiles = cols * rows;
+                self.pan_index = if forward { (self.pan_index + 1) % tiles } else { (self.pan_index + tiles - 1) % tiles };
+                Ok(())
+            }
+            _ => {
+                if forward { self.next_image() } else { self.prev_image() }
+            }
+        }
+    }
+    // screen pixels per image pixel
+    fn scale(&self) -> f32 {
+        let (img_width, img_height) = self.decoded.as_ref().map(|img| img.dimensions()).unwrap_or((1, 1));
+        let fit_scale = (WIDTH as f32 / img_width as f32).min(HEIGHT as f32 / img_height as f32);
+        match self.mode {
+            ViewMode::Zoom(zoom) => fit_scale * zoom as f32,
+            _ => fit_scale,
+        }
+    }
+    // how many screen-sized tiles the zoomed image is split into
+    fn tile_grid(&self) -> (usize, usize) {
+        let (img_width, img_height) = self.decoded.as_ref().map(|img| img.dimensions()).unwrap_or((1, 1));
+        let scale = self.scale();
+        let cols = ((img_width as f32 * scale) / WIDTH as f32).ceil().max(1.0) as usize;
+        let rows = ((img_height as f32 * scale) / HEIGHT as f32).ceil().max(1.0) as usize;
+        (cols, rows)
+    }
+    // full frame in framebuffer byte order, ready to be sent as a RawFrame
+    pub fn render(&self, theme: &Theme) -> Vec<u8> {
+        let mut frame = vec![0u8; WIDTH * HEIGHT * 2];
+        clear_screen(&mut frame, theme);
+        if self.loading {
+            let mut display = FramebufferDisplay { buf: &mut frame, width: WIDTH, height: HEIGHT };
+            let point = Point::new((WIDTH as u32 - fonts::text_width(LOADING_TEXT, theme.font)) as i32 / 2, (HEIGHT as u32 - theme.font.character_size.height) as i32 / 2);
+            fonts::draw_text(&mut display, LOADING_TEXT, point, theme.font, theme.muted);
+            return frame;
+        }
+        let Some(img) = &self.decoded else {
+            return frame;
+        };
+        let (img_width, img_height) = img.dimensions();
+        let scale = self.scale();
+        let scaled_width = img_width as f32 * scale;
+        let scaled_height = img_height as f32 * scale;
+
+        // top left of the shown area in scaled image coords, or centered when it fits
+        let (cols, _) = self.tile_grid();
+        let (col, row) = (self.pan_index % cols, self.pan_index / cols);
+        let origin_x = if scaled_width <= WIDTH as f32 { -((WIDTH as f32 - scaled_width) / 2.0) } else { (col as f32 * WIDTH as f32).min(scaled_width - WIDTH as f32) };
+        let origin_y = if scaled_height <= HEIGHT as f32 { -((HEIGHT as f32 - scaled_height) / 2.0) } else { (row as f32 * HEIGHT as f32).min(scaled_height - HEIGHT as f32) };
+
+        // nearest neighbour, plenty for a 320x240 panel
+        let pixels = (0..HEIGHT).flat_map(|y| (0..WIDTH).map(move |x| (x, y))).filter_map(|(x, y)| {
+            let src_x = (origin_x + x as f32) / scale;
+            let src_y = (origin_y + y as f32) / scale;
+            if src_x < 0.0 || src_y < 0.0 || src_x >= img_width as f32 || src_y >= img_height as f32 {
+                return None;
+            }
+            let [r, g, b] = img.get_pixel(src_x as u32, src_y as u32).0;
+            Some(Pixel(Point::new(x as i32, y as i32), Rgb565::new(r >> 3, g >> 2, b >> 3)))
+        });
+        let mut display = FramebufferDisplay { buf: &mut frame, width: WIDTH, height: HEIGHT };
+        display.draw_iter(pixels).unwrap();
+        frame
+    }
+}
+
+// the path it was for, so a result for an image that's been flipped past can be dropped
+type DecodeResult = Arc<Mutex<Option<(PathBuf, Result<RgbImage, String>)>>>;
+
+struct Decoder {
+    tx: mpsc::Sender<PathBuf>,
+    // filled in right before ImageDecoded is sent
+    decoded: DecodeResult,
+}
+
+fn start_decode_task(tx: mpsc::Sender<ButtonEvent>) -> Decoder {
+    let (path_tx, path_rx) = mpsc::channel::<PathBuf>();
+    let decoded = Arc::new(Mutex::new(None));
+    let decoded1 = decoded.clone();
+    thread::spawn(move || {
+        decode_task(path_rx, tx, decoded1);
+    });
+    Decoder { tx: path_tx, decoded }
+}
+
+// big jpegs take a while on the pi, so they're decoded here instead of holding up the main loop
+fn decode_task(rx: mpsc::Receiver<PathBuf>, tx: mpsc::Sender<ButtonEvent>, decoded: DecodeResult) {
+    while let Ok(mut path) = rx.recv() {
+        // skip anything that was flipped past while we were busy
+        while let Ok(newer_path) = rx.try_recv() {
+            path = newer_path;
+        }
+        let image = image::open(&path)
+            .map(|image| image.to_rgb8())
+            .map_err(|e| format!("Image error: Could not decode {}: {}", path.display(), e));
+        *decoded.lock().unwrap() = Some((path, image));
+        if tx.send(ButtonEvent::ImageDecoded).is_err() {
+            break;
+        }
+    }
+}
+
+// advances the slideshow while it's on
+pub fn slideshow_task(tx: mpsc::Sender<ButtonEvent>, slideshow: Arc<AtomicBool>) {
+    loop {
+        thread::sleep(SLIDESHOW_INTERVAL);
+        if slideshow.load(Ordering::Acquire) {
+            tx.send(ButtonEvent::SlideshowTick).unwrap();
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use embedded_graphics::prelude::*;
+
+use crate::{theme::Role, widgets::{Node, Screen, WidgetKind}, ButtonEvent, HEIGHT, WIDTH};
+
+// one page of keys each, the shift key goes round them in this order
+const LAYERS: [&str; 3] = [
+    "abcdefghijklmnopqrstuvwxyz0123456789",
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
+    "._-,'!?&()@#+=:;/~%$*\"<>[]{}^|\\`",
+];
+// after each layer's chars, walked to w/ up/down like any other key
+const SPECIAL_KEYS: [Special; 4] = [Special::Shift, Special::Space, Special::Delete, Special::Done];
+// one per key on the biggest layer, the ones a smaller layer doesn't use stay blank
+const KEY_IDS: [&str; 40] = [
+    "key_0", "key_1", "key_2", "key_3", "key_4", "key_5", "key_6", "key_7", "key_8", "key_9",
+    "key_10", "key_11", "key_12", "key_13", "key_14", "key_15", "key_16", "key_17", "key_18", "key_19",
+    "key_20", "key_21", "key_22", "key_23", "key_24", "key_25", "key_26", "key_27", "key_28", "key_29",
+    "key_30", "key_31", "key_32", "key_33", "key_34", "key_35", "key_36", "key_37", "key_38", "key_39",
+];
+const COLUMNS: usize = 10;
+const KEY_WIDTH: i32 = 26;
+const KEY_HEIGHT: i32 = 22;
+const GRID_TOP_LEFT: Point = Point::new(30, 90);
+// longest text you can type
+const MAX_LEN: usize = 40;
+// inside the box's border, the start gets cut off past this
+const TEXT_BOX_WIDTH: u32 = 256;
+const HINT: &str = "hold: up/down a row, select done, esc delete";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Special {
+    Shift,
+    Space,
+    Delete,
+    Done,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum KeyResult {
+    // still going, needs redrawing
+    Typing,
+    Done(String),
+    Cancelled,
+    // not something the keyboard uses (ex. TimeChanged), nothing changed
+    Ignored,
+}
+
+// on screen keyboard for anything that needs text typed in. the caller opens it w/ a title and starting text,
+// then passes it every button event until it's done or cancelled.
+// up/down walk the keys, select types, escape cancels. held: up/down jump a row, select finishes, escape deletes
+#[derive(Clone, Debug)]
+pub struct Keyboard {
+    pub title: String,
+    pub text: String,
+    pub cursor: usize,
+    layer: usize,
+}
+impl Keyboard {
+    pub fn new() -> Keyboard {
+        Keyboard { title: String::new(), text: String::new(), cursor: 0, layer: 0 }
+    }
+    pub fn open(&mut self, title: &str, text: &str) {
+        self.title = title.to_string();
+        self.text = text.to_string();
+        self.cursor = 0;
+        self.layer = 0;
+    }
+    fn chars(&self) -> &'static [u8] {
+        LAYERS[self.layer].as_bytes()
+    }
+    fn key_count(&self) -> usize {
+        self.chars().len() + SPECIAL_KEYS.len()
+    }
+    // wraps around both ends
+    fn move_cursor(&mut self, forward: bool, by: usize) {
+        let count = self.key_count();
+        self.cursor = if forward { (self.cursor + by) % count } else { (self.cursor + count - by % count) % count };
+    }
+    fn delete(&mut self) {
+        self.text.pop();
+    }
+    fn push(&mut self, c: char) {
+        if self.text.chars().count() < MAX_LEN {
+            self.text.push(c);
+        }
+    }
+    fn press(&mut self) -> KeyResult {
+        let chars = self.chars();
+        match self.cursor.checked_sub(chars.len()).map(|idx| SPECIAL_KEYS[idx]) {
+            None => self.push(chars[self.cursor] as char),
+            Some(Special::Shift) => {
+                self.layer = (self.layer + 1) % LAYERS.len();
+                // stays on shift so it can be pressed again
+                self.cursor = self.chars().len();
+            }
+            Some(Special::Space) => self.push(' '),
+            Some(Special::Delete) => self.delete(),
+            Some(Special::Done) => return KeyResult::Done(self.text.clone()),
+        }
+        KeyResult::Typing
+    }
+    pub fn handle(&mut self, event: ButtonEvent) -> KeyResult {
+        match event {
+            ButtonEvent::Up => self.move_cursor(false, 1),
+            ButtonEvent::Down => self.move_cursor(true, 1),
+            ButtonEvent::LongUp => self.move_cursor(false, COLUMNS),
+            ButtonEvent::LongDown => self.move_cursor(true, COLUMNS),
+            ButtonEvent::Select => return self.press(),
+            ButtonEvent::LongSelect => return KeyResult::Done(self.text.clone()),
+            ButtonEvent::Escape => return KeyResult::Cancelled,
+            ButtonEvent::LongEscape => self.delete(),
+            _ => return KeyResult::Ignored,
+        }
+        KeyResult::Typing
+    }
+    fn key_label(&self, key: usize) -> String {
+        let chars = self.chars();
+        match key.checked_sub(chars.len()).map(|idx| SPECIAL_KEYS[idx]) {
+            None => (chars[key] as char).to_string(),
+            // shows what it switches to
+            Some(Special::Shift) => String::from(["ABC", "#+=", "abc"][self.layer]),
+            Some(Special::Space) => String::from("spc"),
+            Some(Special::Delete) => String::from("DEL"),
+            Some(Special::Done) => String::from("OK"),
+        }
+    }
+    pub fn screen(&self) -> Screen {
+        let keys = KEY_IDS.iter()
+            .enumerate()
+            .map(|(key, id)| {
+                let offset = Point::new((key % COLUMNS) as i32 * KEY_WIDTH, (key / COLUMNS) as i32 * KEY_HEIGHT);
+                (offset, Node::widget(id, WidgetKind::Button { text: String::new(), selected: false }, KEY_WIDTH as u32 - 2, KEY_HEIGHT as u32 - 2))
+            })
+            .collect();
+        let mut screen = Screen::new(Node::Stack(vec![
+            (Point::new(30, 24), Node::widget("title", WidgetKind::label(""), TEXT_BOX_WIDTH, 14)),
+            (Point::new(28, 48), Node::widget("text", WidgetKind::TextBox(String::new()), TEXT_BOX_WIDTH + 8, 22)),
+            (GRID_TOP_LEFT, Node::Stack(keys)),
+            (Point::new(30, 204), Node::widget("hint", WidgetKind::Label { text: String::from(HINT), color: Role::Muted }, TEXT_BOX_WIDTH, 14)),
+        ]), WIDTH, HEIGHT);
+        self.update(&mut screen);
+        screen
+    }
+    // moving the cursor only redraws the two keys it moved between
+    pub fn update(&self, screen: &mut Screen) {
+        screen.set("title", WidgetKind::label(&self.title));
+        screen.set("text", WidgetKind::TextBox(self.text.clone()));
+        for (key, id) in KEY_IDS.iter().enumerate() {
+            let text = if key < self.key_count() { self.key_label(key) } else { String::new() };
+            screen.set(id, WidgetKind::Button { text, selected: key == self.cursor });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ButtonEvent::*;
+
+    // opened on "ab", then each event in turn. what the last one returned and where it ended up
+    fn run(events: &[ButtonEvent]) -> (KeyResult, Keyboard) {
+        let mut keyboard = Keyboard::new();
+        keyboard.open("Rename", "ab");
+        let mut result = KeyResult::Ignored;
+        for &event in events {
+            result = keyboard.handle(event);
+        }
+        (result, keyboard)
+    }
+
+    #[test]
+    fn switches_layers() {
+        let lower = LAYERS[0].len();
+        let symbols = LAYERS[2].len();
+        // (events, layer, cursor, text)
+        let cases: [(&[ButtonEvent], usize, usize, &str); 6] = [
+            (&[Select], 0, 0, "aba"),
+            // up from the first key wraps to done, 3 more to shift
+            (&[Up, Up, Up, Up], 0, lower, "ab"),
+            (&[Up, Up, Up, Up, Select], 1, lower, "ab"),
+            (&[Up, Up, Up, Up, Select, Down, Down, Down, Down, Select], 1, 0, "abA"),
+            // the symbols layer is shorter, shift moves w/ it
+            (&[Up, Up, Up, Up, Select, Select], 2, symbols, "ab"),
+            (&[Up, Up, Up, Up, Select, Select, Select], 0, lower, "ab"),
+        ];
+        for (events, layer, cursor, text) in cases {
+            let (result, keyboard) = run(events);
+            assert_eq!(result, KeyResult::Typing, "{:?}", events);
+            assert_eq!((keyboard.layer, keyboard.cursor, keyboard.text.as_str()), (layer, cursor, text), "{:?}", events);
+        }
+    }
+
+    #[test]
+    fn long_presses() {
+        let key_count = LAYERS[0].len() + SPECIAL_KEYS.len();
+        // (events, result, cursor, text)
+        let cases: [(&[ButtonEvent], KeyResult, usize, &str); 8] = [
+            (&[LongDown], KeyResult::Typing, COLUMNS, "ab"),
+            (&[LongDown, Select], KeyResult::Typing, COLUMNS, "abk"),
+            // a row up from the top wraps to the bottom
+            (&[LongUp], KeyResult::Typing, key_count - COLUMNS, "ab"),
+            (&[LongEscape], KeyResult::Typing, 0, "a"),
+            (&[LongEscape, LongEscape, LongEscape], KeyResult::Typing, 0, ""),
+            (&[Select, LongSelect], KeyResult::Done(String::from("aba")), 0, "aba"),
+            (&[Escape], KeyResult::Cancelled, 0, "ab"),
+            (&[TimeChanged], KeyResult::Ignored, 0, "ab"),
+        ];
+        for (events, expected, cursor, text) in cases {
+            let (result, keyboard) = run(events);
+            assert_eq!(result, expected, "{:?}", events);
+            assert_eq!((keyboard.cursor, keyboard.text.as_str()), (cursor, text), "{:?}", events);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use std::{collections::HashSet, ffi::OsString, os::unix::ffi::{OsStrExt, OsStringExt}, path::{Path, PathBuf}, sync::{mpsc, Arc, Mutex}, thread, time::Duration};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{determine_icon_to_draw, dir_model::Entry, thumbnails, utils, ButtonEvent, HEIGHT, SCREEN_FPS, WIDTH};
+
+pub const LIBRARY_PATH: &str = "/home/yassin/cross_compiled/library.db";
+// the dir watcher only sees the dir the navigator is in, this catches changes everywhere else
+const RESCAN_INTERVAL: Duration = Duration::from_mins(10);
+
+// paths are stored as their raw bytes, file names don't have to be utf-8.
+// entries is every file/folder under the media root (what the navigator lists), dirs is which folders
+// have been read into it, videos is the extra metadata for the playable ones
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS dirs (
+        path BLOB PRIMARY KEY
+    );
+    CREATE TABLE IF NOT EXISTS entries (
+        path BLOB PRIMARY KEY,
+        parent BLOB NOT NULL,
+        is_dir INTEGER NOT NULL,
+        size INTEGER NOT NULL,
+        mtime INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS entries_by_parent ON entries (parent);
+    CREATE TABLE IF NOT EXISTS videos (
+        path BLOB PRIMARY KEY,
+        size INTEGER NOT NULL,
+        mtime INTEGER NOT NULL,
+        duration_secs INTEGER NOT NULL,
+        thumbnail BLOB,
+        watched INTEGER NOT NULL DEFAULT 0
+    );
+";
+
+// one indexed video
+#[derive(Clone, PartialEq, Debug)]
+pub struct LibraryEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub mtime: i64,
+    // worked out from the size, see probe
+    pub duration_secs: u64,
+    // cached thumbnail file, None if one couldn't be made
+    pub thumbnail: Option<PathBuf>,
+}
+
+// sqlite file w/ every listing under the media root, so neither the navigator nor the library views
+// have to walk the sd card. a half finished write can't lose what was already in it
+pub struct Library {
+    db: Connection,
+}
+impl Library {
+    // falls back to an in memory index if the file can't be opened, the navigator just reads the disk until it's filled
+    pub fn open() -> Library {
+        let db = Connection::open(LIBRARY_PATH).unwrap_or_else(|e| {
+            println!("could not open library: {}", e);
+            Connection::open_in_memory().unwrap()
+        });
+        if let Err(e) = db.execute_batch(SCHEMA) {
+            println!("could not set up library: {}", e);
+        }
+        Library { db }
+    }
+    // what's in dir, None if it hasn't been indexed (ex. the usb stick, hidden folders, symlinked ones)
+    pub fn list_dir(&self, dir: &Path) -> Option<Vec<Entry>> {
+        self.db.query_row("SELECT 1 FROM dirs WHERE path = ?1", params![path_bytes(dir)], |_| Ok(())).optional().ok()??;
+        let mut statement = self.db.prepare("SELECT path, is_dir, size, mtime FROM entries WHERE parent = ?1").ok()?;
+        let entries = statement.query_map(params![path_bytes(dir)], |row| {
+                let path = bytes_path(row.get(0)?);
+                let is_dir: bool = row.get(1)?;
+                let size: i64 = row.get(2)?;
+                Ok(Entry {
+                    name: path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+                    kind: determine_icon_to_draw(&path, is_dir),
+                    path,
+                    is_dir,
+                    size: size as u64,
+                    mtime: row.get(3)?,
+                })
+            })
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .collect();
+        Some(entries)
+    }
+    // replaces what's stored for dir w/ a fresh read of it. false if nothing changed
+    fn store_dir(&mut self, dir: &Path, entries: &[Entry]) -> bool {
+        if self.list_dir(dir).is_some_and(|mut stored| {
+            stored.sort_by(|a, b| a.path.cmp(&b.path));
+            stored == entries
+        }) {
+            return false;
+        }
+        let result = self.db.transaction().and_then(|transaction| {
+            transaction.execute("DELETE FROM entries WHERE parent = ?1", params![path_bytes(dir)])?;
+            for entry in entries {
+                transaction.execute(
+                    "INSERT OR REPLACE INTO entries (path, parent, is_dir, size, mtime) VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![path_bytes(&entry.path), path_bytes(dir), entry.is_dir, entry.size as i64, entry.mtime],
+                )?;
+            }
+            transaction.execute("INSERT OR IGNORE INTO dirs (path) VALUES (?1)", params![path_bytes(dir)])?;
+            transaction.commit()
+        });
+        if let Err(e) = result {
+            println!("could not save {} to the library: {}", dir.display(), e);
+        }
+        true
+    }
+    // drops dirs (and what was in them) that weren't seen in the last full scan
+    fn forget_dirs_except(&self, seen: &HashSet<PathBuf>) -> bool {
+        let stored: Vec<PathBuf> = match self.db.prepare("SELECT path FROM dirs") {
+            Ok(mut statement) => statement.query_map(params![], |row| Ok(bytes_path(row.get(0)?)))
+                .map(|rows| rows.filter_map(|row| row.ok()).collect())
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        let gone: Vec<&PathBuf> = stored.iter().filter(|dir| !seen.contains(*dir)).collect();
+        for dir in &gone {
+            let result = self.db.execute("DELETE FROM entries WHERE parent = ?1", params![path_bytes(dir)])
+                .and_then(|_| self.db.execute("DELETE FROM dirs WHERE path = ?1", params![path_bytes(dir)]));
+            if let Err(e) = result {
+                println!("could not remove {} from the library: {}", dir.display(), e);
+            }
+        }
+        !gone.is_empty()
+    }
+    fn video(&self, path: &Path) -> Option<LibraryEntry> {
+        self.db.query_row(
+            "SELECT path, size, mtime, duration_secs, thumbnail FROM videos WHERE path = ?1",
+            params![path_bytes(path)],
+            read_video,
+        ).optional().ok()?
+    }
+    // metadata only, whether it's been watched is kept when a file is re-probed
+    fn store_video(&self, video: &LibraryEntry) {
+        let result = self.db.execute(
+            "INSERT INTO videos (path, size, mtime, duration_secs, thumbnail) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (path) DO UPDATE SET size = excluded.size, mtime = excluded.mtime, duration_secs = excluded.duration_secs,
+                 thumbnail = excluded.thumbnail",
+            params![
+                path_bytes(&video.path),
+                video.size as i64,
+                video.mtime,
+                video.duration_secs as i64,
+                video.thumbnail.as_deref().map(path_bytes),
+            ],
+        );
+        if let Err(e) = result {
+            println!("could not save {} to the library: {}", video.path.display(), e);
+        }
+    }
+    fn forget_videos_except(&self, seen: &HashSet<PathBuf>) -> bool {
+        let stored: Vec<PathBuf> = match self.db.prepare("SELECT path FROM videos") {
+            Ok(mut statement) => statement.query_map(params![], |row| Ok(bytes_path(row.get(0)?)))
+                .map(|rows| rows.filter_map(|row| row.ok()).collect())
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        let gone: Vec<&PathBuf> = stored.iter().filter(|path| !seen.contains(*path)).collect();
+        for path in &gone {
+            if let Err(e) = self.db.execute("DELETE FROM videos WHERE path = ?1", params![path_bytes(path)]) {
+                println!("could not remove {} from the library: {}", path.display(), e);
+            }
+        }
+        !gone.is_empty()
+    }
+    // set once it's been played close to the end, stays set even after it falls off the recent list
+    pub fn mark_watched(&self, path: &Path) {
+        if let Err(e) = self.db.execute("UPDATE videos SET watched = 1 WHERE path = ?1", params![path_bytes(path)]) {
+            println!("could not mark {} watched: {}", path.display(), e);
+        }
+    }
+    // shortest first, w/ the duration after the name
+    pub fn by_duration(&self, unwatched_only: bool) -> Vec<Entry> {
+        let query = if unwatched_only {
+            "SELECT path, size, mtime, duration_secs, thumbnail FROM videos WHERE watched = 0 ORDER BY duration_secs"
+        }
+        else {
+            "SELECT path, size, mtime, duration_secs, thumbnail FROM videos ORDER BY duration_secs"
+        };
+        let videos: Vec<LibraryEntry> = match self.db.prepare(query) {
+            Ok(mut statement) => statement.query_map(params![], read_video)
+                .map(|rows| rows.filter_map(|row| row.ok()).collect())
+                .unwrap_or_default(),
+            Err(e) => {
+                println!("could not read the library: {}", e);
+                Vec::new()
+            }
+        };
+        videos.into_iter()
+            .map(|video| Entry {
+                name: format!("{} {}", video.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(), utils::format_duration(video.duration_secs)),
+                kind: determine_icon_to_draw(&video.path, false),
+                path: video.path,
+                is_dir: false,
+                size: video.size,
+                mtime: video.mtime,
+            })
+            .collect()
+    }
+}
+fn read_video(row: &rusqlite::Row) -> rusqlite::Result<LibraryEntry> {
+    let size: i64 = row.get(1)?;
+    let duration_secs: i64 = row.get(3)?;
+    let thumbnail: Option<Vec<u8>> = row.get(4)?;
+    Ok(LibraryEntry {
+        path: bytes_path(row.get(0)?),
+        size: size as u64,
+        mtime: row.get(2)?,
+        duration_secs: duration_secs as u64,
+        thumbnail: thumbnail.map(bytes_path),
+    })
+}
+fn path_bytes(path: &Path) -> &[u8] {
+    path.as_os_str().as_bytes()
+}
+fn bytes_path(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
+// owned by the main loop, the index itself is shared w/ the indexer thread
+pub struct Indexer {
+    pub library: Arc<Mutex<Library>>,
+    rescan_tx: mpsc::Sender<()>,
+}
+impl Indexer {
+    // unchanged files are reused as-is, so this is just a walk + a stat per file
+    pub fn rescan(&self) {
+        self.rescan_tx.send(()).unwrap();
+    }
+    // re-reads one dir right away (the dir watcher saw it change), so the navigator can list it from the index.
+    // new videos in it get probed by the rescan this kicks off
+    pub fn refresh(&self, dir: &Path) {
+        let indexed = self.library.lock().unwrap().list_dir(dir).is_some();
+        if indexed && let Some(entries) = read_dir_sorted(dir) {
+            self.library.lock().unwrap().store_dir(dir, &entries);
+        }
+        self.rescan();
+    }
+}
+
+// indexes once on startup, then whenever rescan() is called or every RESCAN_INTERVAL. sends LibraryChanged when the index changes
+pub fn start_indexer(root: PathBuf, skip: PathBuf, tx: mpsc::Sender<ButtonEvent>) -> Indexer {
+    let library = Arc::new(Mutex::new(Library::open()));
+    let (rescan_tx, rescan_rx) = mpsc::channel::<()>();
+    let library1 = library.clone();
+    thread::spawn(move || {
+        indexer_task(root, skip, library1, rescan_rx, tx);
+    });
+    rescan_tx.send(()).unwrap();
+    Indexer { library, rescan_tx }
+}
+
+fn indexer_task(root: PathBuf, skip: PathBuf, library: Arc<Mutex<Library>>, rescan_rx: mpsc::Receiver<()>, tx: mpsc::Sender<ButtonEvent>) {
+    loop {
+        match rescan_rx.recv_timeout(RESCAN_INTERVAL) {
+            Ok(()) | Err(mpsc::RecvTimeoutError::Timeout) => (),
+            Err(mpsc::RecvTimeoutError::Disconnected) => return,
+        }
+        // one scan covers however many asked for it while we were busy
+        while rescan_rx.try_recv().is_ok() {}
+
+        // the lock is only held per dir/video, the navigator reads from it while this runs
+        let mut changed = false;
+        let mut dirs = Vec::new();
+        let mut videos = Vec::new();
+        walk(&root, &skip, &library, &mut dirs, &mut videos, &mut changed);
+        for (path, size, mtime) in &videos {
+            // reuse what we had if the file hasn't changed
+            let known = library.lock().unwrap().video(path);
+            if known.is_some_and(|video| video.size == *size && video.mtime == *mtime) {
+                continue;
+            }
+            // probing makes the thumbnail, so it's done w/o holding the lock
+            let video = probe(path.clone(), *size, *mtime);
+            library.lock().unwrap().store_video(&video);
+            changed = true;
+        }
+        let video_paths: HashSet<PathBuf> = videos.into_iter().map(|(path, _, _)| path).collect();
+        {
+            let library = library.lock().unwrap();
+            changed |= library.forget_dirs_except(&dirs.into_iter().collect());
+            changed |= library.forget_videos_except(&video_paths);
+        }
+        if changed {
+            println!("library index updated: {} videos", video_paths.len());
+            if tx.send(ButtonEvent::LibraryChanged).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+// everything in dir the way the navigator lists it (symlinks followed for the size/type), by path
+fn read_dir_sorted(dir: &Path) -> Option<Vec<Entry>> {
+    let mut entries: Vec<Entry> = std::fs::read_dir(dir).ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| Entry::from_path(entry.path()))
+        .collect();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Some(entries)
+}
+
+// stores every dir under dir, collecting playable files w/ their size/mtime. doesn't go into the usb stick,
+// hidden folders or symlinked dirs (so loops can't hang it), those are read off the disk when navigated into
+fn walk(dir: &Path, skip: &Path, library: &Mutex<Library>, dirs: &mut Vec<PathBuf>, videos: &mut Vec<(PathBuf, u64, i64)>, changed: &mut bool) {
+    let Some(entries) = read_dir_sorted(dir) else {
+        return;
+    };
+    *changed |= library.lock().unwrap().store_dir(dir, &entries);
+    dirs.push(dir.to_owned());
+    for entry in entries {
+        if entry.path == skip || entry.name.starts_with('.') {
+            continue;
+        }
+        let is_real_dir = std::fs::symlink_metadata(&entry.path).is_ok_and(|meta| meta.is_dir());
+        if is_real_dir {
+            walk(&entry.path, skip, library, dirs, videos, changed);
+        }
+        else if !entry.is_dir && thumbnails::is_playable(&entry.path) {
+            videos.push((entry.path, entry.size, entry.mtime));
+        }
+    }
+}
+
+// the raw format is fixed: headerless rgb565 frames at the screen's size, played at SCREEN_FPS. there's no
+// metadata in the file to read and nothing else to store, so the duration is just the size over that
+fn probe(path: PathBuf, size: u64, mtime: i64) -> LibraryEntry {
+    let frame_bytes = (WIDTH * HEIGHT * 2) as u64;
+    let thumbnail = thumbnails::ensure_thumbnail(&path);
+    LibraryEntry { path, size, mtime, duration_secs: size / frame_bytes / SCREEN_FPS as u64, thumbnail }
+}
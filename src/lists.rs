@@ -0,0 +1,172 @@
+use std::{ffi::OsStr, os::unix::{ffi::OsStrExt, fs::MetadataExt}, path::{Path, PathBuf}};
+
+use crate::{dir_model::{DirModel, Entry, Filter, SortKey}, library::{self, Library}, utils, IconKind};
+
+const FAVORITES_PATH: &str = "/home/yassin/cross_compiled/favorites.txt";
+const RECENT_PATH: &str = "/home/yassin/cross_compiled/recent.txt";
+// how many played videos are remembered
+const MAX_RECENT: usize = 20;
+// brackets so they can't be mistaken for (or clash with) real folders in the media root
+const FAVORITES_NAME: &str = "[Favorites]";
+const RECENT_NAME: &str = "[Recent]";
+const ALL_VIDEOS_NAME: &str = "[All videos]";
+const UNWATCHED_NAME: &str = "[Unwatched]";
+
+// folders that don't exist on disk, listed at the top of the media root
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum VirtualDir {
+    Favorites,
+    Recent,
+    // the last two come from the library index
+    AllVideos,
+    Unwatched,
+}
+const VIRTUAL_DIRS: [VirtualDir; 4] = [VirtualDir::Favorites, VirtualDir::Recent, VirtualDir::AllVideos, VirtualDir::Unwatched];
+impl VirtualDir {
+    fn name(&self) -> &'static str {
+        match self {
+            VirtualDir::Favorites => FAVORITES_NAME,
+            VirtualDir::Recent => RECENT_NAME,
+            VirtualDir::AllVideos => ALL_VIDEOS_NAME,
+            VirtualDir::Unwatched => UNWATCHED_NAME,
+        }
+    }
+    // the file its contents are saved in
+    fn list_path(&self) -> &'static str {
+        match self {
+            VirtualDir::Favorites => FAVORITES_PATH,
+            VirtualDir::Recent => RECENT_PATH,
+            VirtualDir::AllVideos | VirtualDir::Unwatched => library::LIBRARY_PATH,
+        }
+    }
+}
+
+pub struct RecentEntry {
+    pub path: PathBuf,
+    pub frame: u64,
+    pub total_frames: u64,
+}
+
+// starred and recently played, both saved on every change
+pub struct Lists {
+    // in the order they were starred
+    pub favorites: Vec<PathBuf>,
+    // most recently played first
+    pub recent: Vec<RecentEntry>,
+}
+impl Lists {
+    pub fn load() -> Lists {
+        let favorites = read_lines(FAVORITES_PATH).into_iter().map(bytes_path).collect();
+        // one "frame<tab>total_frames<tab>path" per line
+        let recent = read_lines(RECENT_PATH).into_iter()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, |&byte| byte == b'\t');
+                let frame = std::str::from_utf8(fields.next()?).ok()?.parse().ok()?;
+                let total_frames = std::str::from_utf8(fields.next()?).ok()?.parse().ok()?;
+                Some(RecentEntry { path: bytes_path(fields.next()?), frame, total_frames })
+            })
+            .collect();
+        Lists { favorites, recent }
+    }
+    pub fn is_starred(&self, path: &Path) -> bool {
+        self.favorites.iter().any(|favorite| favorite == path)
+    }
+    // returns whether it's starred now
+    pub fn toggle_star(&mut self, path: &Path) -> bool {
+        let starred = if self.is_starred(path) {
+            self.favorites.retain(|favorite| favorite != path);
+            false
+        }
+        else {
+            self.favorites.push(path.to_owned());
+            true
+        };
+        let mut contents = Vec::new();
+        for favorite in self.favorites.iter().filter(|favorite| savable(favorite)) {
+            contents.extend_from_slice(favorite.as_os_str().as_bytes());
+            contents.push(b'\n');
+        }
+        if let Err(e) = utils::write_atomic(FAVORITES_PATH, &contents) {
+            println!("could not save favorites: {:#?}", e);
+        }
+        starred
+    }
+    // moves it to the top, w/ how far in we got
+    pub fn record_played(&mut self, path: &Path, frame: u64, total_frames: u64) {
+        self.recent.retain(|recent| recent.path != path);
+        self.recent.insert(0, RecentEntry { path: path.to_owned(), frame, total_frames });
+        self.recent.truncate(MAX_RECENT);
+        let mut contents = Vec::new();
+        for recent in self.recent.iter().filter(|recent| savable(&recent.path)) {
+            contents.extend_from_slice(format!("{}\t{}\t", recent.frame, recent.total_frames).as_bytes());
+            contents.extend_from_slice(recent.path.as_os_str().as_bytes());
+            contents.push(b'\n');
+        }
+        if let Err(e) = utils::write_atomic(RECENT_PATH, &contents) {
+            println!("could not save recent: {:#?}", e);
+        }
+    }
+    // where to pick a video back up from, 0 if it was never played or was watched to the end
+    pub fn resume_frame(&self, path: &Path) -> u64 {
+        match self.recent.iter().find(|recent| recent.path == path) {
+            Some(recent) if recent.frame < recent.total_frames => recent.frame,
+            _ => 0,
+        }
+    }
+    // the listing for a virtual folder. files that vanished (or are on a usb stick that's not plugged in)
+    // are skipped but kept in the list, in case they come back. library views trust the index, it's rescanned on changes
+    pub fn dir_model(&self, virtual_dir: VirtualDir, library: &Library, root: &Path, sort_key: SortKey, filter: Filter) -> DirModel {
+        let entries = match virtual_dir {
+            VirtualDir::Favorites => self.favorites.iter()
+                .filter(|path| path.exists())
+                .map(|path| Entry::from_path(path.clone()))
+                .collect(),
+            VirtualDir::Recent => self.recent.iter()
+                .filter(|recent| recent.path.exists())
+                .map(|recent| {
+                    let mut entry = Entry::from_path(recent.path.clone());
+                    let percent = (recent.frame * 100).checked_div(recent.total_frames).unwrap_or(0);
+                    entry.name = format!("{} {}%", entry.name, percent);
+                    entry
+                })
+                .collect(),
+            VirtualDir::AllVideos => library.by_duration(false),
+            // never played near the end, the index keeps track so it doesn't depend on what's still in recent
+            VirtualDir::Unwatched => library.by_duration(true),
+        };
+        DirModel { path: root.join(virtual_dir.name()), entries, sort_key, filter }
+    }
+}
+
+// paths are saved as their raw bytes, same as in the library, so non utf-8 names survive.
+// one per line, which is why a name w/ a newline in it can't be saved
+fn read_lines(list_path: &str) -> Vec<Vec<u8>> {
+    std::fs::read(list_path)
+        .map(|contents| contents.split(|&byte| byte == b'\n').filter(|line| !line.is_empty()).map(<[u8]>::to_vec).collect())
+        .unwrap_or_default()
+}
+fn bytes_path(bytes: impl AsRef<[u8]>) -> PathBuf {
+    PathBuf::from(OsStr::from_bytes(bytes.as_ref()))
+}
+fn savable(path: &Path) -> bool {
+    !path.as_os_str().as_bytes().contains(&b'\n')
+}
+
+// which virtual folder path is, if any
+pub fn virtual_dir_at(root: &Path, path: &Path) -> Option<VirtualDir> {
+    VIRTUAL_DIRS.into_iter().find(|virtual_dir| path == root.join(virtual_dir.name()))
+}
+// the entries for the virtual folders themselves, shown at the top of the media root
+pub fn virtual_entries(root: &Path) -> Vec<Entry> {
+    VIRTUAL_DIRS.into_iter()
+        .map(|virtual_dir| Entry {
+            name: virtual_dir.name().to_string(),
+            path: root.join(virtual_dir.name()),
+            is_dir: true,
+            size: 0,
+            // when the list last changed
+            mtime: std::fs::metadata(virtual_dir.list_path()).map(|meta| meta.mtime()).unwrap_or(0),
+            kind: IconKind::Folder,
+        })
+        .collect()
+}
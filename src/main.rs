@@ -1,16 +1,16 @@
 use chrono::{DateTime, Local};
 use embedded_graphics::{
-    image::{Image, ImageRaw, ImageRawLE}, mono_font::{ascii::{FONT_6X10, FONT_8X13}, MonoTextStyle, MonoTextStyleBuilder}, pixelcolor::{BinaryColor, Rgb565}, prelude::*, primitives::{PrimitiveStyleBuilder, Rectangle, RoundedRectangle}, text::{Baseline, Text}
+    image::{Image, ImageRaw, ImageRawLE}, mono_font::{ascii::FONT_8X13, MonoTextStyleBuilder}, pixelcolor::{BinaryColor, Rgb565}, prelude::*, primitives::{PrimitiveStyle, PrimitiveStyleBuilder, Rectangle}, text::{Baseline, Text}
 };
 use embedded_hal::digital::{InputPin, OutputPin};
 use linux_embedded_hal::{gpio_cdev::{AsyncLineEventHandle, Chip, EventRequestFlags, EventType, LineRequestFlags}, I2cdev};
 use linux_embedded_hal::i2cdev::core::I2CDevice;
 use linux_embedded_hal::{ CdevPin };
-use std::{collections::HashMap, fs::DirEntry, io::prelude::*, os::unix::{ffi::OsStringExt, fs::MetadataExt}, path::PathBuf, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex}};
+use std::{collections::HashMap, io::prelude::*, os::unix::ffi::OsStringExt, path::{Path, PathBuf}, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex}};
 use std::fs::File;
 use std::thread;
 use std::sync::mpsc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use linuxfb::Framebuffer;
 use ssd1306::{mode::BufferedGraphicsMode, prelude::*, I2CDisplayInterface, Ssd1306};
 use futures::StreamExt;
@@ -18,8 +18,6 @@ use debouncr::{debounce_4, Debouncer, Edge, Repeat4};
 use std::process::Command;
 use openweathermap::{blocking::weather, init, update};
 
-use crate::draw::{BOTTOM_CAROUSEL_ICON_COORDS, ENTRY_META_FILESIZE_TEXT_COORDS, MIDDLE_CAROUSEL_ICON_COORDS, TOP_CAROUSEL_ICON_COORDS};
-
 
 const WIDTH: usize = 320;
 const HEIGHT: usize = 240;
@@ -27,10 +25,39 @@ const HEIGHT: usize = 240;
 const SSD1306_SLAVE_ADDR: u16 = 0x3c;
 const SCREEN_FPS: u8 = 24;
 const PIXELS_PER_FRAME: u32 = 153_600;
+// how long a button has to be held to count as a long press
+const LONG_PRESS: Duration = Duration::from_millis(600);
+// entries skipped by a long press of up/down in the navigator
+const PAGE_JUMP: usize = 10;
+// how far into a video you have to get before it's off the unwatched list
+const WATCHED_PERCENT: u64 = 90;
 
 // mods
+mod animation;
+mod back_buffer;
+mod config;
+mod dir_model;
+mod dir_watcher;
 mod draw;
+mod file_ops;
+mod fonts;
+mod hud;
+mod hex_viewer;
+mod image_viewer;
+mod keyboard;
+mod library;
+mod lists;
+mod modal;
+mod nav_panel;
+mod search;
+mod settings;
+mod text_reader;
+mod theme;
+mod thumbnails;
+mod toast;
+mod usb;
 mod utils;
+mod widgets;
 
 #[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 enum ButtonEvent {
@@ -40,30 +67,96 @@ enum ButtonEvent {
     Escape,
     TimeChanged,
     CurrentFrameChanged,
+    SlideshowTick,
+    DirChanged,
+    // a usb stick was plugged in or pulled out
+    UsbChanged,
+    // the indexer found new/changed/removed videos
+    LibraryChanged,
+    // held for LONG_PRESS
+    LongUp,
+    LongDown,
+    LongSelect,
+    LongEscape,
+    // from the copy/move thread, only while Transferring
+    TransferProgress,
+    TransferDone,
+    // the image viewer's decoder finished, only matters while ViewingImage
+    ImageDecoded,
+}
+impl ButtonEvent {
+    // what a long press falls back to in states that don't use it
+    fn short(self) -> ButtonEvent {
+        match self {
+            ButtonEvent::LongUp => ButtonEvent::Up,
+            ButtonEvent::LongDown => ButtonEvent::Down,
+            ButtonEvent::LongSelect => ButtonEvent::Select,
+            ButtonEvent::LongEscape => ButtonEvent::Escape,
+            other => other,
+        }
+    }
 }
 #[derive(Clone, Copy, Debug)]
 enum DisplayState {
     Navigating,
+    // typing on the keyboard to jump to an entry in the navigator
+    Searching,
+    // long select on an entry, list of things to do with it
+    ContextMenu,
+    ConfirmingDelete,
+    Renaming,
+    // copy/move w/ a progress bar
+    Transferring,
     PlayingSomething,
     ConfirmingMediaSelection,
     ConfirmingMediaExit,
     UnrecoverableError,
-    ErrorMessage,
+    ViewingImage,
+    ReadingText,
+    Settings,
+    // typing in a text setting (the weather location) w/ the keyboard
+    EditingSetting,
+    InspectingHex,
+}
+impl DisplayState {
+    // the rest only ever see short presses, so those go out on press instead of waiting for the release
+    fn uses_long_press(&self) -> bool {
+        // the keyboard has its own long press shortcuts
+        matches!(self, DisplayState::Navigating | DisplayState::Searching | DisplayState::Renaming | DisplayState::EditingSetting)
+    }
 }
 struct State {
     current_state: Arc<Mutex<DisplayState>>,
     previous_state: DisplayState,
     nav_state: NavigatingData,
     video_state: PlayingSomethingData,
+    image_state: image_viewer::ImageViewerData,
+    reader_state: text_reader::TextReaderData,
+    hex_state: hex_viewer::HexViewerData,
+    usb_state: usb::UsbState,
+    search_state: search::SearchData,
+    menu_state: file_ops::ContextMenu,
+    keyboard_state: keyboard::Keyboard,
+    settings_menu: settings::SettingsMenu,
+    // the weather task refetches as soon as this changes
+    weather_location_tx: mpsc::Sender<String>,
+    // for the sleep timeout, the backlight goes off w/o a press for config.sleep_after minutes
+    last_press: Instant,
+    asleep: bool,
+    // what "Paste here" will copy/move
+    clipboard: Option<file_ops::Clipboard>,
+    transfer: Option<file_ops::Transfer>,
     modal_state: Option<ModalState>,
     error_state: String,
     current_time: Arc<Mutex<DateTime<Local>>>,
     current_weather: Arc<Mutex<String>>,
+    config: config::Config,
+    // the draw task keeps its own copy, this one is for frames rendered here
+    theme: theme::Theme,
 }
 #[derive(Clone)]
 struct ModalState {
-    message: String,
-    selected: u8,
+    modal: modal::Modal,
     file: Option<FileDetails>,
 }
 #[derive(Clone)]
@@ -76,13 +169,30 @@ struct FileDetails {
     last_modified: String,
 }
 struct NavigatingData {
-    current_dir: PathBuf,
+    // loaded once per directory, every nav function indexes into this
+    dir: dir_model::DirModel,
     current_index: usize,
-    file_count: usize,
+    // media root from the config, escape stops here
+    root: PathBuf,
+    // dir => entry that was selected when we left it
+    cursor_history: HashMap<PathBuf, PathBuf>,
+    // favorites and recently played, shown as virtual folders in the media root
+    lists: lists::Lists,
+    // every video under the media root, for the library views
+    indexer: library::Indexer,
+    // off in the config for a snappier carousel
+    animations: bool,
+}
+impl NavigatingData {
+    fn transition(&self, transition: nav_panel::Transition) -> nav_panel::Transition {
+        if self.animations { transition } else { nav_panel::Transition::None }
+    }
 }
 #[derive(Clone)]
 struct PlayingSomethingData {
     paused: Arc<AtomicBool>,
+    // bumped every time playback starts, a thread from an older one stops instead of sending frames alongside the new one
+    playback: Arc<AtomicU64>,
     // framerate is 24fps, so for example, frame 480 would be 20 seconds into the video
     current_frame: Arc<AtomicU64>,
     volume: Arc<AtomicU64>,
@@ -151,8 +261,7 @@ fn main() -> ! {
         .iter()
         .last()
         .unwrap()
-        .to_str()
-        .unwrap();
+        .to_string_lossy();
 
     // buttons channels and tasks-------------------------------------------------------------
     // btn channel
@@ -163,62 +272,105 @@ fn main() -> ! {
     // video/music task command channel (pause, resume, stop), prob wont use anymore
     // let (media_tx, mut media_rx) = mpsc::channel::<ControlCommand>(128);
 
-    let file_count = std::fs::read_dir(std::env::current_dir().unwrap().as_path()).unwrap().count();
+    let config = config::Config::load();
+    // start at the media root, canonical so it compares cleanly against paths built while navigating
+    let root = std::fs::canonicalize(&config.media_root).unwrap_or_else(|e| {
+        println!("media root {} not usable, using {}: {:#?}", config.media_root.display(), current_dir.display(), e);
+        current_dir.clone()
+    });
+    let dir = dir_model::DirModel::empty(&root, config.sort_key, config.filter);
+    let theme = theme::Theme::load(&config.theme);
+    draw_tx.send(DrawCommand::Theme { theme: theme.clone() }).unwrap();
+    draw_tx.send(DrawCommand::Brightness { percent: config.brightness }).unwrap();
+    let (weather_location_tx, weather_location_rx) = mpsc::channel::<String>();
+    let weather_location = config.weather_location.clone();
     // this'll give you: 2069-01-24 13:17:44.609871 UTC or something.
     let current_local_time: DateTime<Local> = Local::now();
-    let formatted_local_time = current_local_time.format("%-I:%M%P").to_string();
-    println!("file count!: {}", file_count);
+    let formatted_local_time = format_clock(&current_local_time, config.clock_24h);
     println!("formatted local time: {:?}", formatted_local_time);
-    draw_tx.send(DrawCommand::Text { content: formatted_local_time, position: draw::TOP_NAV_CLOCK_TEXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+    draw_tx.send(DrawCommand::Clock { time: formatted_local_time }).unwrap();
 
 
+    let usb_state = usb::UsbState::new(&config.sysfs_root, &root);
+    // indexes in the background, the usb stick is left out since it comes and goes
+    let indexer = library::start_indexer(root.clone(), usb_state.mount_point.clone(), btn_tx.clone());
+
     let mut state = State {
         current_state: Arc::new(Mutex::new(DisplayState::Navigating)),
         previous_state: DisplayState::Navigating,
         nav_state: NavigatingData {
-            current_dir: current_dir.clone(),
-            file_count: file_count,
+            dir,
             current_index: 0,
+            root,
+            cursor_history: HashMap::new(),
+            lists: lists::Lists::load(),
+            indexer,
+            animations: config.animations,
         },
         video_state: PlayingSomethingData {
             paused: Arc::new(AtomicBool::new(false)),
+            playback: Arc::new(AtomicU64::new(0)),
             current_frame: Arc::new(AtomicU64::new(0)),
             total_frames: Arc::new(AtomicU64::new(0)),
             volume: Arc::new(AtomicU64::new(0)),
             drawn_timestamp: String::from("0:00 / 0:00"),
         },
+        image_state: image_viewer::ImageViewerData::new(btn_tx.clone()),
+        reader_state: text_reader::TextReaderData::new(),
+        hex_state: hex_viewer::HexViewerData::new(),
+        usb_state,
+        search_state: search::SearchData::new(),
+        menu_state: file_ops::ContextMenu::new(),
+        keyboard_state: keyboard::Keyboard::new(),
+        settings_menu: settings::SettingsMenu::new(&config),
+        weather_location_tx,
+        last_press: Instant::now(),
+        asleep: false,
+        clipboard: None,
+        transfer: None,
         modal_state: None,
         error_state: String::new(),
         current_time: Arc::new(Mutex::new(current_local_time)),
         current_weather: Arc::new(Mutex::new(String::from("?"))),
+        config,
+        theme,
     };
 
+    // the root listing needs the lists loaded for the virtual folders
+    match load_dir(&state.nav_state, &state.nav_state.root) {
+        Ok(dir) => state.nav_state.dir = dir,
+        Err(e) => println!("could not read media root {}: {:#?}", state.nav_state.root.display(), e),
+    }
+
     // select
     let btn_tx1 = btn_tx.clone();
+    let current_state1 = state.current_state.clone();
     thread::spawn(move || {
-        button_task(chip_path, 19, btn_tx1.clone(), ButtonEvent::Select)
+        button_task(chip_path, 19, btn_tx1.clone(), current_state1, ButtonEvent::Select, Some(ButtonEvent::LongSelect))
     });
     // escape
     let btn_tx1 = btn_tx.clone();
+    let current_state1 = state.current_state.clone();
     thread::spawn(move || {
-        button_task(chip_path, 26, btn_tx1.clone(), ButtonEvent::Escape);
+        button_task(chip_path, 26, btn_tx1.clone(), current_state1, ButtonEvent::Escape, Some(ButtonEvent::LongEscape));
     });
     // up
     let btn_tx1 = btn_tx.clone();
+    let current_state1 = state.current_state.clone();
     thread::spawn(move || {
-        button_task(chip_path, 13, btn_tx1.clone(), ButtonEvent::Up);
+        button_task(chip_path, 13, btn_tx1.clone(), current_state1, ButtonEvent::Up, Some(ButtonEvent::LongUp));
     });
     // down
     let btn_tx1 = btn_tx.clone();
+    let current_state1 = state.current_state.clone();
     thread::spawn(move || {
-        button_task(chip_path, 6, btn_tx1.clone(), ButtonEvent::Down);
+        button_task(chip_path, 6, btn_tx1.clone(), current_state1, ButtonEvent::Down, Some(ButtonEvent::LongDown));
     });
     // time changer
     let btn_tx1 = btn_tx.clone();
     let current_time1 = state.current_time.clone();
-    let current_state1 = state.current_state.clone();
     thread::spawn(move || {
-        current_time_task(btn_tx1.clone(), current_time1, current_state1);
+        current_time_task(btn_tx1.clone(), current_time1);
     });
     // watch frames and change timestamp on 2nd screen when applicable
     let btn_tx1 = btn_tx.clone();
@@ -229,64 +381,138 @@ fn main() -> ! {
         current_frame_task(btn_tx1.clone(), current_frame1, total_frames1, paused1);
     });
 
+    // slideshow - flips to the next image every few secs while the image viewer is in slideshow mode
+    let btn_tx1 = btn_tx.clone();
+    let slideshow1 = state.image_state.slideshow.clone();
+    thread::spawn(move || {
+        image_viewer::slideshow_task(btn_tx1.clone(), slideshow1);
+    });
+
     // draw task - will draw whatever until end of program
     thread::spawn(|| {
         start_drawing_task(draw_rx);
     });
 
-    // weather task - draws whenever weather changes if only in navigation state
-    let current_state1 = state.current_state.clone();
+    // weather task - sends the weather whenever it changes, the navigator keeps it while it's hidden
     let current_weather1 = state.current_weather.clone();
     let draw_tx1 = draw_tx.clone();
-    draw_tx.send(DrawCommand::Text { content: "?".to_string(), position: draw::TOP_NAV_WEATHER_TEXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+    draw_tx.send(DrawCommand::Weather { weather: "?".to_string() }).unwrap();
+    thread::spawn(move || {
+        current_weather_task(current_weather1, weather_location, weather_location_rx, draw_tx1);
+    });
+
+    // thumbnail task - generates thumbnails for the selected entry in the background
+    let thumbnail_requester = thumbnails::start_thumbnail_task(draw_tx.clone(), state.current_state.clone());
+
+    // usb task - listens for usb sticks coming and going
+    let btn_tx1 = btn_tx.clone();
+    let sysfs_root1 = state.config.sysfs_root.clone();
     thread::spawn(move || {
-        current_weather_task(current_state1, current_weather1,draw_tx1);
+        usb::usb_task(btn_tx1.clone(), sysfs_root1);
     });
 
+    // dir watcher - sends DirChanged when files show up/disappear in the dir the navigator is in
+    let mut dir_watcher = dir_watcher::start_dir_watch_task(btn_tx.clone());
+    dir_watcher.watch(&state.nav_state.dir.path);
+
     // wait for tasks to be ready or something idk, maybe mostly drawing task to init i2c and spi
     std::thread::sleep(Duration::from_millis(200)); 
 
     draw_tx.send(DrawCommand::ClearScreen).unwrap();
     std::thread::sleep(Duration::from_millis(200));
 
-    draw_tx.send(DrawCommand::NavigatingBackground { current_dir: current_dir.clone(), file_count: file_count, current_index: state.nav_state.current_index }).unwrap();
+    draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
+    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
 
     // listen for btn presses
     loop {
         while let Ok(event) = btn_rx.recv() {
             let current_state = *state.current_state.lock().unwrap();
+            // a press while the backlight's off just wakes it up
+            if let ButtonEvent::Up | ButtonEvent::Down | ButtonEvent::Select | ButtonEvent::Escape | ButtonEvent::LongUp | ButtonEvent::LongDown | ButtonEvent::LongSelect | ButtonEvent::LongEscape = event {
+                state.last_press = Instant::now();
+                if state.asleep {
+                    state.asleep = false;
+                    backlight.set_high().unwrap();
+                    continue;
+                }
+            }
+            // only dozes off in the navigator, never mid video
+            if let ButtonEvent::TimeChanged = event && matches!(current_state, DisplayState::Navigating | DisplayState::Searching)
+                && !state.asleep && state.config.sleep_after > 0 && state.last_press.elapsed() >= Duration::from_mins(state.config.sleep_after) {
+                state.asleep = true;
+                backlight.set_low().unwrap();
+            }
+            // the navigator keeps the clock even while it's hidden, so every state keeps it current
+            if let ButtonEvent::TimeChanged = event {
+                redraw_clock(&state, &draw_tx);
+                continue;
+            }
+            // reload even when not navigating, so going back to the navigator is never stale
+            if let ButtonEvent::DirChanged = event {
+                state.nav_state.indexer.refresh(&state.nav_state.dir.path);
+                let navigating = matches!(current_state, DisplayState::Navigating);
+                refresh_dir(&mut state.nav_state, navigating, &draw_tx);
+                if navigating {
+                    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                }
+                continue;
+            }
+            if let ButtonEvent::LibraryChanged = event {
+                // real dirs are listed from the index too, so whatever we're in might've changed.
+                // favorites/recent are the only listings that don't come out of it
+                if !matches!(lists::virtual_dir_at(&state.nav_state.root, &state.nav_state.dir.path), Some(lists::VirtualDir::Favorites | lists::VirtualDir::Recent)) {
+                    let navigating = matches!(current_state, DisplayState::Navigating);
+                    refresh_dir(&mut state.nav_state, navigating, &draw_tx);
+                    if navigating {
+                        thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                    }
+                }
+                continue;
+            }
+            if let ButtonEvent::UsbChanged = event {
+                // the mount point showing up/going away in the media root gets picked up by the dir watcher
+                if state.usb_state.refresh() {
+                    // the mount point comes and goes w/ the stick
+                    state.nav_state.indexer.refresh(&state.nav_state.root);
+                    show_toast(&draw_tx, if state.usb_state.device.is_some() { "USB connected" } else { "USB removed" }, toast::ToastKind::Info);
+                    if state.usb_state.device.is_none() && state.nav_state.dir.path.starts_with(&state.usb_state.mount_point) {
+                        // pulled out from under us
+                        let navigating = matches!(current_state, DisplayState::Navigating);
+                        leave_to_root(&mut state.nav_state, navigating, &draw_tx);
+                    }
+                }
+                continue;
+            }
+            // a long press that started before the state changed
+            let event = if current_state.uses_long_press() { event } else { event.short() };
             match current_state {
                 DisplayState::Navigating => {
                     match event {
                         ButtonEvent::Escape => {
-                            // go up dir or show error msg
+                            // go up dir, unless we're already at the media root
                             println!("Clicked escape!");
-                            // TODO!() use Result type instead
-                            let res = exit_dir(&state.nav_state, draw_tx.clone());
-                            match res {
-                                Some((path, file_count)) => {
-                                    state.nav_state.current_dir = path;
-                                    state.nav_state.current_index = 0;
-                                    state.nav_state.file_count = file_count;
+                            match exit_dir(&state.nav_state, draw_tx.clone()) {
+                                ExitResponse::Directory(dir, index) => {
+                                    switch_dir(&mut state.nav_state, dir, index);
                                 }
-                                None => {
-                                    // show error? idk, maybe use Err(msg) instead
-                                    {
-                                        let current_state = state.current_state.clone();
-                                        let mut current_state = current_state.lock().unwrap();
-                                        *current_state = DisplayState::ErrorMessage;
-                                    }
-                                    // set modal state to none here if u want idk
-                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
-                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Error! x_x".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
-                                    draw_tx.send(DrawCommand::ConfirmingBackground { message: format!("Unknown error..."), options: vec!["Okay".to_string()] }).unwrap();
+                                ExitResponse::AtRoot => {
+                                    show_toast(&draw_tx, "At top", toast::ToastKind::Info);
+                                }
+                                ExitResponse::Error(err_msg) => {
+                                    show_error(&mut state, &draw_tx, err_msg, "Navigating");
                                 }
                             }
                         }
                         ButtonEvent::Select => {
                             // go into dir or show confirmmediaselection
                             println!("Clicked Select!");
-                            let res = enter_dir_or_select_file(&state.nav_state, draw_tx.clone());
+                            // the usb stick only gets mounted once we actually go into it
+                            let usb_selected = selected_entry_path(&state.nav_state).is_some_and(|path| state.usb_state.is_mount_point(&path));
+                            let res = match usb_selected.then(|| state.usb_state.mount()) {
+                                Some(Err(err_msg)) => SelectResponse::Error(err_msg),
+                                _ => enter_dir_or_select_file(&state.nav_state, draw_tx.clone()),
+                            };
                             match res {
                                 SelectResponse::File { file_name, file_size, file_extension, file_path, last_modified } => {
                                     println!("this file extension is: {}", file_extension);
@@ -295,45 +521,83 @@ fn main() -> ! {
                                     let draw_tx = draw_tx.clone();
                                     match file_extension.as_str() {
                                         "rgb565" | "raw" => {
-                                            state.modal_state = Some(ModalState { message: format!("Play video: {}?", file_name), selected: 0, file: Some(FileDetails { file_path, file_size, file_name: file_name.clone(), file_extension: file_extension.clone(), is_dir: false, last_modified })});
+                                            // picks up where it was left off last time
+                                            state.video_state.current_frame.store(state.nav_state.lists.resume_frame(&file_path), Ordering::Relaxed);
+                                            let modal = modal::Modal::new(&format!("Play video: {}?", file_name), &["No!", "Yes!"]);
+                                            state.modal_state = Some(ModalState { modal: modal.clone(), file: Some(FileDetails { file_path, file_size, file_name: file_name.clone(), file_extension: file_extension.clone(), is_dir: false, last_modified })});
                                             state.video_state.total_frames.store(file_size, Ordering::Relaxed);
                                             {
                                                 let current_state = state.current_state.clone();
                                                 let mut current_state = current_state.lock().unwrap();
                                                 *current_state = DisplayState::ConfirmingMediaSelection;
                                             }
-                                            draw_tx.send(DrawCommand::ConfirmingBackground { message: format!("Play video: {}?", file_name), options: vec!["No!".to_string(), "Yes!".to_string()] }).unwrap();
+                                            draw_tx.send(DrawCommand::ConfirmingBackground { modal }).unwrap();
                                             draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                                             draw_tx.send(DrawCommand::DrawI2CText { content: "Confirm?".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
                                         }
-                                        _ =>  {
-                                            state.modal_state = Some(ModalState { message: "Can not currently play this kind of file - handling of different files (such as txt's, and other basic formats) are in development!".to_string(), selected: 0, file:Some(FileDetails { file_path, file_size, file_name: file_name.clone(), file_extension: file_extension.clone(), last_modified, is_dir: false })});
-                                            {
-                                                let current_state = state.current_state.clone();
-                                                let mut current_state = current_state.lock().unwrap();
-                                                *current_state = DisplayState::ErrorMessage;
+                                        _ if image_viewer::is_image(&file_path) => {
+                                            match state.image_state.open(&file_path, &state.nav_state.dir) {
+                                                Ok(()) => {
+                                                    {
+                                                        let current_state = state.current_state.clone();
+                                                        let mut current_state = current_state.lock().unwrap();
+                                                        *current_state = DisplayState::ViewingImage;
+                                                    }
+                                                    draw_tx.send(DrawCommand::RawFrame { data: state.image_state.render(&state.theme) }).unwrap();
+                                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                                                    draw_tx.send(DrawCommand::DrawI2CText { content: format!("Img {}", state.image_state.mode.label()), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                                                }
+                                                Err(err_msg) => {
+                                                    show_error(&mut state, &draw_tx, err_msg, "Navigating");
+                                                }
+                                            }
+                                        }
+                                        _ if text_reader::is_text(&file_path) => {
+                                            match state.reader_state.open(&file_path) {
+                                                Ok(()) => {
+                                                    {
+                                                        let current_state = state.current_state.clone();
+                                                        let mut current_state = current_state.lock().unwrap();
+                                                        *current_state = DisplayState::ReadingText;
+                                                    }
+                                                    draw_tx.send(DrawCommand::RawFrame { data: state.reader_state.render(&state.theme) }).unwrap();
+                                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Reading".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                                                    state.reader_state.drawn_position = state.reader_state.position();
+                                                    draw_tx.send(DrawCommand::DrawI2CText { content: state.reader_state.drawn_position.clone(), position: draw::SECOND_LINE_COORDS, undraw: false, screen: false }).unwrap();
+                                                }
+                                                Err(err_msg) => {
+                                                    show_error(&mut state, &draw_tx, err_msg, "Navigating");
+                                                }
+                                            }
+                                        }
+                                        _ => {
+                                            // anything else gets the hex inspector
+                                            let opened = state.hex_state.open(&file_path).and_then(|_| state.hex_state.render(&state.theme));
+                                            match opened {
+                                                Ok(frame) => {
+                                                    {
+                                                        let current_state = state.current_state.clone();
+                                                        let mut current_state = current_state.lock().unwrap();
+                                                        *current_state = DisplayState::InspectingHex;
+                                                    }
+                                                    draw_tx.send(DrawCommand::RawFrame { data: frame }).unwrap();
+                                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                                                    draw_tx.send(DrawCommand::DrawI2CText { content: state.hex_state.title(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                                                    draw_tx.send(DrawCommand::DrawI2CText { content: state.hex_state.magic.clone(), position: draw::SECOND_LINE_COORDS, undraw: false, screen: false }).unwrap();
+                                                }
+                                                Err(err_msg) => {
+                                                    show_error(&mut state, &draw_tx, err_msg, "Navigating");
+                                                }
                                             }
-                                            draw_tx.send(DrawCommand::ConfirmingBackground { message: "Can not currently play this kind of file - handling of different files (such as txt's, and other basic formats) are in development!".to_string(), options: vec!["Okay".to_string()] }).unwrap();
-                                            draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
-                                            draw_tx.send(DrawCommand::DrawI2CText { content: "Error! x_x".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
                                         }
                                     }
                                 }
-                                SelectResponse::Directory { file_path, file_count, last_modified } => {
-                                    state.nav_state.current_dir = file_path;
-                                    state.nav_state.current_index = 0;
-                                    state.nav_state.file_count = file_count;
+                                SelectResponse::Directory(dir, index) => {
+                                    switch_dir(&mut state.nav_state, dir, index);
                                 }
                                 SelectResponse::Error(err_msg) => {
-                                    state.modal_state = Some(ModalState { message: err_msg.clone(), selected: 0, file: None });
-                                    {
-                                        let current_state = state.current_state.clone();
-                                        let mut current_state = current_state.lock().unwrap();
-                                        *current_state = DisplayState::ErrorMessage;
-                                    }
-                                    draw_tx.send(DrawCommand::ConfirmingBackground { message: err_msg, options: vec!["Okay".to_string()] }).unwrap();
-                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
-                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Error! x_x".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                                    show_error(&mut state, &draw_tx, err_msg, "Navigating");
                                 }
                                 SelectResponse::FatalError(fatal_err_msg) => {
                                     {
@@ -341,8 +605,9 @@ fn main() -> ! {
                                         let mut current_state = current_state.lock().unwrap();
                                         *current_state = DisplayState::UnrecoverableError;
                                     }
-                                    state.modal_state = Some(ModalState { message: fatal_err_msg.clone(), selected: 0, file: None });
-                                    draw_tx.send(DrawCommand::ConfirmingBackground { message: fatal_err_msg, options: vec!["Okay".to_string()] }).unwrap();
+                                    let modal = modal::Modal::new(&fatal_err_msg, &["Okay"]);
+                                    state.modal_state = Some(ModalState { modal: modal.clone(), file: None });
+                                    draw_tx.send(DrawCommand::ConfirmingBackground { modal }).unwrap();
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "FATAL ERROR!!".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
                                 }
@@ -351,39 +616,412 @@ fn main() -> ! {
                         ButtonEvent::Up => {
                             // goto prev file
                             println!("Clicked Up!");
-                            if state.nav_state.file_count > 1 {
-                                if state.nav_state.current_index != 0 {
-                                    let draw_tx = draw_tx.clone();
-                                    scroll_up(&state.nav_state, draw_tx);
-                                    state.nav_state.current_index -= 1;
-                                }
-                            }
+                            scroll_up(&mut state.nav_state, &draw_tx);
                         }
                         ButtonEvent::Down => {
                             // goto next file
                             println!("Clicked Down!");
-                            if state.nav_state.file_count > 1 {
-                                if state.nav_state.current_index != (state.nav_state.file_count - 1) {
-                                    let draw_tx = draw_tx.clone();
-                                    scroll_down(&state.nav_state, draw_tx);
-                                    state.nav_state.current_index += 1;
+                            scroll_down(&mut state.nav_state, &draw_tx);
+                        }
+                        ButtonEvent::LongUp => {
+                            // page up
+                            let new_index = state.nav_state.current_index.saturating_sub(PAGE_JUMP);
+                            jump_to(&mut state.nav_state, new_index, &draw_tx);
+                        }
+                        ButtonEvent::LongDown => {
+                            // page down
+                            let new_index = (state.nav_state.current_index + PAGE_JUMP).min(state.nav_state.dir.len().saturating_sub(1));
+                            jump_to(&mut state.nav_state, new_index, &draw_tx);
+                        }
+                        ButtonEvent::LongSelect => {
+                            // context menu for whatever's selected
+                            let selected = selected_entry_path(&state.nav_state);
+                            let in_virtual_dir = lists::virtual_dir_at(&state.nav_state.root, &state.nav_state.dir.path).is_some();
+                            let on_usb = state.nav_state.dir.path.starts_with(&state.usb_state.mount_point);
+                            let mut actions = Vec::new();
+                            match &selected {
+                                Some(path) if state.usb_state.is_mount_point(path) => actions.push(file_ops::MenuAction::Eject),
+                                // the virtual folders themselves can't be touched
+                                Some(path) if lists::virtual_dir_at(&state.nav_state.root, path).is_some() => (),
+                                Some(path) => {
+                                    actions.push(if state.nav_state.lists.is_starred(path) { file_ops::MenuAction::Unstar } else { file_ops::MenuAction::Star });
+                                    // the stick is mounted read only, copying off it is all that works
+                                    if !on_usb {
+                                        actions.extend([file_ops::MenuAction::Rename, file_ops::MenuAction::Move]);
+                                    }
+                                    actions.push(file_ops::MenuAction::Copy);
+                                    if !on_usb {
+                                        actions.push(file_ops::MenuAction::Delete);
+                                    }
                                 }
+                                None => (),
                             }
-                        }
-                        ButtonEvent::TimeChanged => {
+                            if state.clipboard.is_some() && !in_virtual_dir && !on_usb {
+                                actions.push(file_ops::MenuAction::Paste);
+                            }
+                            actions.push(file_ops::MenuAction::Settings);
+                            state.menu_state.open(selected, actions);
                             {
-                                let current_time = state.current_time.lock().unwrap();
-                                draw_tx.send(DrawCommand::Text { content: current_time.format("%-I:%M%P").to_string(), position: draw::TOP_NAV_CLOCK_TEXT_COORDS, undraw: true, is_selected: false,}).unwrap();
+                                let current_state = state.current_state.clone();
+                                let mut current_state = current_state.lock().unwrap();
+                                *current_state = DisplayState::ContextMenu;
                             }
-                            let new_current_local_time: DateTime<Local> = Local::now();
-                            let new_formatted_local_time = new_current_local_time.format("%-I:%M%P").to_string();
+                            draw_tx.send(DrawCommand::ConfirmingBackground { modal: state.menu_state.modal.clone() }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: "Menu".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                        }
+                        ButtonEvent::LongEscape => {
+                            // search mode, typed on the keyboard
+                            state.search_state.reset();
+                            state.keyboard_state.open(search::TITLE, "");
                             {
-                                let mut current_time = state.current_time.lock().unwrap();
-                                *current_time = new_current_local_time;
+                                let current_state = state.current_state.clone();
+                                let mut current_state = current_state.lock().unwrap();
+                                *current_state = DisplayState::Searching;
                             }
-                            draw_tx.send(DrawCommand::Text { content: new_formatted_local_time, position: draw::TOP_NAV_CLOCK_TEXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+                            draw_tx.send(DrawCommand::Panel { panel: Panel::Keyboard(state.keyboard_state.clone()) }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: "Search".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                            state.search_state.drawn_query = state.search_state.label();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.search_state.drawn_query.clone(), position: draw::SECOND_LINE_COORDS, undraw: false, screen: false }).unwrap();
                         }
                         ButtonEvent::CurrentFrameChanged => {}
+                        ButtonEvent::SlideshowTick => {}
+                        // handled before this match
+                        ButtonEvent::TimeChanged | ButtonEvent::DirChanged | ButtonEvent::UsbChanged | ButtonEvent::LibraryChanged => {}
+                        ButtonEvent::TransferProgress | ButtonEvent::TransferDone | ButtonEvent::ImageDecoded => {}
+                    }
+                    // keep the thumbnail beside the selected entry in sync
+                    match event {
+                        ButtonEvent::Up | ButtonEvent::Down | ButtonEvent::Select | ButtonEvent::Escape | ButtonEvent::LongUp | ButtonEvent::LongDown | ButtonEvent::LongSelect => {
+                            // not held while selecting, the thumbnail task takes the state lock before the selection one
+                            let navigating = matches!(*state.current_state.lock().unwrap(), DisplayState::Navigating);
+                            if navigating {
+                                thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                            }
+                            // virtual folders aren't on disk, the watch just stays on the last real dir
+                            if lists::virtual_dir_at(&state.nav_state.root, &state.nav_state.dir.path).is_none() {
+                                dir_watcher.watch(&state.nav_state.dir.path);
+                            }
+                            // left the usb stick, unmount it so it's safe to pull out
+                            if !state.nav_state.dir.path.starts_with(&state.usb_state.mount_point) {
+                                state.usb_state.unmount();
+                            }
+                        }
+                        _ => ()
+                    }
+                }
+                DisplayState::Searching => {
+                    match state.keyboard_state.handle(event) {
+                        keyboard::KeyResult::Typing => {
+                            state.search_state.query = state.keyboard_state.text.to_lowercase();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.search_state.drawn_query.clone(), position: draw::SECOND_LINE_COORDS, undraw: true, screen: false }).unwrap();
+                            state.search_state.drawn_query = state.search_state.label();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.search_state.drawn_query.clone(), position: draw::SECOND_LINE_COORDS, undraw: false, screen: false }).unwrap();
+                            state.keyboard_state.title = state.search_state.title(&state.nav_state.dir);
+                            draw_tx.send(DrawCommand::Panel { panel: Panel::Keyboard(state.keyboard_state.clone()) }).unwrap();
+                        }
+                        result @ (keyboard::KeyResult::Done(_) | keyboard::KeyResult::Cancelled) => {
+                            // done lands on the match, no match or cancelling stays where it was
+                            if let keyboard::KeyResult::Done(_) = result
+                                && let Some(new_index) = state.search_state.find(&state.nav_state.dir) {
+                                state.nav_state.current_index = new_index;
+                            }
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.search_state.drawn_query.clone(), position: draw::SECOND_LINE_COORDS, undraw: true, screen: false }).unwrap();
+                            return_to_navigator(&mut state, &draw_tx, "Search");
+                            thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                        }
+                        keyboard::KeyResult::Ignored => (),
+                    }
+                }
+                DisplayState::ContextMenu => {
+                    match event {
+                        ButtonEvent::Up | ButtonEvent::Down => {
+                            state.menu_state.modal.move_selection(event == ButtonEvent::Down, &state.theme);
+                            draw_tx.send(DrawCommand::ModalUpdate { modal: state.menu_state.modal.clone() }).unwrap();
+                        }
+                        ButtonEvent::Escape => {
+                            return_to_navigator(&mut state, &draw_tx, "Menu");
+                            thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                        }
+                        ButtonEvent::Select => {
+                            let target = state.menu_state.target.clone();
+                            match (state.menu_state.selected_action(), target) {
+                                (Some(file_ops::MenuAction::Star | file_ops::MenuAction::Unstar), Some(target)) => {
+                                    return_to_navigator(&mut state, &draw_tx, "Menu");
+                                    let starred = state.nav_state.lists.toggle_star(&target);
+                                    // unstarring from inside favorites takes it off the list right away
+                                    if lists::virtual_dir_at(&state.nav_state.root, &state.nav_state.dir.path) == Some(lists::VirtualDir::Favorites) {
+                                        refresh_dir(&mut state.nav_state, true, &draw_tx);
+                                    }
+                                    show_toast(&draw_tx, if starred { "Added to favorites" } else { "Removed from favorites" }, toast::ToastKind::Info);
+                                    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                                }
+                                (Some(file_ops::MenuAction::Settings), _) => {
+                                    state.settings_menu = settings::SettingsMenu::new(&state.config);
+                                    {
+                                        let current_state = state.current_state.clone();
+                                        let mut current_state = current_state.lock().unwrap();
+                                        *current_state = DisplayState::Settings;
+                                    }
+                                    draw_tx.send(DrawCommand::ConfirmingBackground { modal: state.settings_menu.modal.clone() }).unwrap();
+                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Menu".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Settings".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                                }
+                                (Some(file_ops::MenuAction::Eject), _) => {
+                                    return_to_navigator(&mut state, &draw_tx, "Menu");
+                                    state.usb_state.unmount();
+                                    if state.usb_state.mounted {
+                                        show_toast(&draw_tx, "USB busy", toast::ToastKind::Error);
+                                    }
+                                    else {
+                                        show_toast(&draw_tx, "Safe to remove", toast::ToastKind::Info);
+                                    }
+                                    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                                }
+                                (Some(file_ops::MenuAction::Rename), Some(target)) => {
+                                    // starts from the current name
+                                    let name = target.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                                    state.keyboard_state.open("Rename to:", &name);
+                                    {
+                                        let current_state = state.current_state.clone();
+                                        let mut current_state = current_state.lock().unwrap();
+                                        *current_state = DisplayState::Renaming;
+                                    }
+                                    draw_tx.send(DrawCommand::Panel { panel: Panel::Keyboard(state.keyboard_state.clone()) }).unwrap();
+                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Menu".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Rename".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                                }
+                                (Some(action @ (file_ops::MenuAction::Move | file_ops::MenuAction::Copy)), Some(target)) => {
+                                    // nothing happens until it's pasted somewhere
+                                    let operation = if action == file_ops::MenuAction::Move { file_ops::Operation::Move } else { file_ops::Operation::Copy };
+                                    state.clipboard = Some(file_ops::Clipboard { path: target, operation });
+                                    return_to_navigator(&mut state, &draw_tx, "Menu");
+                                    show_toast(&draw_tx, "Go paste it", toast::ToastKind::Info);
+                                    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                                }
+                                (Some(file_ops::MenuAction::Paste), _) => {
+                                    let clipboard = state.clipboard.clone().unwrap();
+                                    match file_ops::start_transfer(clipboard, &state.nav_state.dir.path, btn_tx.clone()) {
+                                        Ok(transfer) => {
+                                            {
+                                                let current_state = state.current_state.clone();
+                                                let mut current_state = current_state.lock().unwrap();
+                                                *current_state = DisplayState::Transferring;
+                                            }
+                                            draw_tx.send(DrawCommand::Panel { panel: Panel::Transfer(transfer.progress()) }).unwrap();
+                                            draw_tx.send(DrawCommand::DrawI2CText { content: "Menu".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                                            draw_tx.send(DrawCommand::DrawI2CText { content: "Transferring".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                                            state.transfer = Some(transfer);
+                                        }
+                                        Err(err_msg) => {
+                                            show_error(&mut state, &draw_tx, err_msg, "Menu");
+                                        }
+                                    }
+                                }
+                                (Some(file_ops::MenuAction::Delete), Some(target)) => {
+                                    let name = target.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                                    let message = format!("Delete {}?", name);
+                                    let modal = modal::Modal::new(&message, &["No!", "Yes!"]);
+                                    state.modal_state = Some(ModalState { modal: modal.clone(), file: None });
+                                    {
+                                        let current_state = state.current_state.clone();
+                                        let mut current_state = current_state.lock().unwrap();
+                                        *current_state = DisplayState::ConfirmingDelete;
+                                    }
+                                    draw_tx.send(DrawCommand::ConfirmingBackground { modal }).unwrap();
+                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Menu".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                                    draw_tx.send(DrawCommand::DrawI2CText { content: "Delete?".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                                }
+                                _ => ()
+                            }
+                        }
+                        _ => ()
+                    }
+                }
+                DisplayState::ConfirmingDelete => {
+                    match event {
+                        ButtonEvent::Escape => {
+                            return_to_navigator(&mut state, &draw_tx, "Delete?");
+                            thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                        }
+                        ButtonEvent::Select => {
+                            let confirmed = state.modal_state.as_ref().is_some_and(|modal_state| modal_state.modal.selected == 1);
+                            let result = match (confirmed, &state.menu_state.target) {
+                                (true, Some(target)) => Some(file_ops::delete(target)),
+                                _ => None,
+                            };
+                            match result {
+                                Some(Err(err_msg)) => {
+                                    show_error(&mut state, &draw_tx, err_msg, "Delete?");
+                                }
+                                Some(Ok(())) => {
+                                    refresh_dir(&mut state.nav_state, false, &draw_tx);
+                                    return_to_navigator(&mut state, &draw_tx, "Delete?");
+                                    show_toast(&draw_tx, "Deleted", toast::ToastKind::Info);
+                                    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                                }
+                                None => {
+                                    return_to_navigator(&mut state, &draw_tx, "Delete?");
+                                    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                                }
+                            }
+                        }
+                        ButtonEvent::Up => {
+                            if let Some(modal_state) = &mut state.modal_state {
+                                modal_state.modal.move_selection(false, &state.theme);
+                                draw_tx.send(DrawCommand::ModalUpdate { modal: modal_state.modal.clone() }).unwrap();
+                            }
+                        }
+                        ButtonEvent::Down => {
+                            if let Some(modal_state) = &mut state.modal_state {
+                                modal_state.modal.move_selection(true, &state.theme);
+                                draw_tx.send(DrawCommand::ModalUpdate { modal: modal_state.modal.clone() }).unwrap();
+                            }
+                        }
+                        _ => ()
+                    }
+                }
+                DisplayState::Renaming => {
+                    match state.keyboard_state.handle(event) {
+                        keyboard::KeyResult::Typing => {
+                            draw_tx.send(DrawCommand::Panel { panel: Panel::Keyboard(state.keyboard_state.clone()) }).unwrap();
+                        }
+                        keyboard::KeyResult::Done(new_name) => {
+                            let Some(target) = state.menu_state.target.clone() else {
+                                continue;
+                            };
+                            match file_ops::rename(&target, &new_name) {
+                                Ok(new_path) => {
+                                    // stars follow the file
+                                    if state.nav_state.lists.is_starred(&target) {
+                                        state.nav_state.lists.toggle_star(&target);
+                                        state.nav_state.lists.toggle_star(&new_path);
+                                    }
+                                    refresh_dir(&mut state.nav_state, false, &draw_tx);
+                                    if let Some(new_index) = state.nav_state.dir.position_of(&new_path) {
+                                        state.nav_state.current_index = new_index;
+                                    }
+                                    return_to_navigator(&mut state, &draw_tx, "Rename");
+                                    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                                }
+                                Err(err_msg) => {
+                                    // still in the keyboard so the name can be fixed up
+                                    show_toast(&draw_tx, &err_msg, toast::ToastKind::Error);
+                                }
+                            }
+                        }
+                        keyboard::KeyResult::Cancelled => {
+                            return_to_navigator(&mut state, &draw_tx, "Rename");
+                            thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                        }
+                        keyboard::KeyResult::Ignored => (),
+                    }
+                }
+                DisplayState::Settings => {
+                    match event {
+                        ButtonEvent::Up | ButtonEvent::Down => {
+                            state.settings_menu.modal.move_selection(event == ButtonEvent::Down, &state.theme);
+                            draw_tx.send(DrawCommand::ModalUpdate { modal: state.settings_menu.modal.clone() }).unwrap();
+                        }
+                        ButtonEvent::Select => {
+                            let setting = state.settings_menu.selected_setting();
+                            if setting.is_text() {
+                                state.keyboard_state.open("Weather for:", &state.config.weather_location);
+                                {
+                                    let current_state = state.current_state.clone();
+                                    let mut current_state = current_state.lock().unwrap();
+                                    *current_state = DisplayState::EditingSetting;
+                                }
+                                draw_tx.send(DrawCommand::Panel { panel: Panel::Keyboard(state.keyboard_state.clone()) }).unwrap();
+                                continue;
+                            }
+                            setting.step(&mut state.config);
+                            apply_setting(&mut state, setting, &draw_tx);
+                            state.settings_menu.refresh(&state.config);
+                            draw_tx.send(DrawCommand::ModalUpdate { modal: state.settings_menu.modal.clone() }).unwrap();
+                        }
+                        ButtonEvent::Escape => {
+                            return_to_navigator(&mut state, &draw_tx, "Settings");
+                            thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                        }
+                        _ => ()
+                    }
+                }
+                DisplayState::EditingSetting => {
+                    match state.keyboard_state.handle(event) {
+                        keyboard::KeyResult::Typing => {
+                            draw_tx.send(DrawCommand::Panel { panel: Panel::Keyboard(state.keyboard_state.clone()) }).unwrap();
+                        }
+                        result @ (keyboard::KeyResult::Done(_) | keyboard::KeyResult::Cancelled) => {
+                            if let keyboard::KeyResult::Done(location) = result {
+                                // empty would just break the weather
+                                if !location.trim().is_empty() {
+                                    state.config.weather_location = location.trim().to_string();
+                                    apply_setting(&mut state, settings::Setting::WeatherLocation, &draw_tx);
+                                }
+                            }
+                            // back to the list either way
+                            {
+                                let current_state = state.current_state.clone();
+                                let mut current_state = current_state.lock().unwrap();
+                                *current_state = DisplayState::Settings;
+                            }
+                            state.settings_menu.refresh(&state.config);
+                            draw_tx.send(DrawCommand::ConfirmingBackground { modal: state.settings_menu.modal.clone() }).unwrap();
+                        }
+                        keyboard::KeyResult::Ignored => (),
+                    }
+                }
+                DisplayState::Transferring => {
+                    match event {
+                        ButtonEvent::TransferProgress => {
+                            // only redraw when the percent moves, progress comes in every chunk
+                            if let Some(transfer) = &mut state.transfer {
+                                let percent = transfer.percent();
+                                if percent != transfer.drawn_percent {
+                                    transfer.drawn_percent = percent;
+                                    draw_tx.send(DrawCommand::Panel { panel: Panel::Transfer(transfer.progress()) }).unwrap();
+                                }
+                            }
+                        }
+                        ButtonEvent::Escape => {
+                            // the thread cleans up the partial copy, then sends TransferDone
+                            if let Some(transfer) = &state.transfer {
+                                transfer.cancel.store(true, Ordering::Release);
+                            }
+                        }
+                        ButtonEvent::TransferDone => {
+                            let Some(transfer) = state.transfer.take() else {
+                                continue;
+                            };
+                            let cancelled = transfer.cancel.load(Ordering::Acquire);
+                            match transfer.take_result() {
+                                Err(err_msg) if !cancelled => {
+                                    refresh_dir(&mut state.nav_state, false, &draw_tx);
+                                    show_error(&mut state, &draw_tx, err_msg, "Transferring");
+                                }
+                                result => {
+                                    // a moved file is gone from where it was, nothing left to paste
+                                    if result.is_ok() && transfer.clipboard.operation == file_ops::Operation::Move {
+                                        state.clipboard = None;
+                                        // stars follow the file
+                                        if state.nav_state.lists.is_starred(&transfer.clipboard.path) {
+                                            state.nav_state.lists.toggle_star(&transfer.clipboard.path);
+                                            state.nav_state.lists.toggle_star(&transfer.dest);
+                                        }
+                                    }
+                                    refresh_dir(&mut state.nav_state, false, &draw_tx);
+                                    if let Some(new_index) = state.nav_state.dir.position_of(&transfer.dest) {
+                                        state.nav_state.current_index = new_index;
+                                    }
+                                    return_to_navigator(&mut state, &draw_tx, "Transferring");
+                                    show_toast(&draw_tx, if cancelled { "Cancelled" } else { "Pasted" }, toast::ToastKind::Info);
+                                    thumbnail_requester.select(selected_entry_path(&state.nav_state), &draw_tx);
+                                }
+                            }
+                        }
+                        _ => ()
                     }
                 }
                 DisplayState::ConfirmingMediaSelection => {
@@ -397,16 +1035,16 @@ fn main() -> ! {
                             }
                             // set modal state to none here if u want idk
                             if let Some(modal_state) = &mut state.modal_state {
-                                modal_state.selected = 0;
+                                modal_state.modal.selected = 0;
                             }
                             draw_tx.send(DrawCommand::DrawI2CText { content: "Confirm?".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                             draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
-                            draw_tx.send(DrawCommand::NavigatingBackground { current_dir: state.nav_state.current_dir.clone(), file_count: state.nav_state.file_count, current_index: state.nav_state.current_index }).unwrap();
+                            draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
                         }
                         ButtonEvent::Select => {
                             // go back or goto playing based on state
                             if let Some(modal_state) = &mut state.modal_state {
-                                if modal_state.selected == 0 {
+                                if modal_state.modal.selected == 0 {
                                     {
                                         let current_state = state.current_state.clone();
                                         let mut current_state = current_state.lock().unwrap();
@@ -415,9 +1053,9 @@ fn main() -> ! {
                                     // set modal state to none here if u want idk
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Confirm?".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
-                                    draw_tx.send(DrawCommand::NavigatingBackground { current_dir: state.nav_state.current_dir.clone(), file_count: state.nav_state.file_count, current_index: state.nav_state.current_index }).unwrap();
+                                    draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
                                 }
-                                else if modal_state.selected == 1 {
+                                else if modal_state.modal.selected == 1 {
                                     let draw_tx = draw_tx.clone();
                                     let current_state = state.current_state.clone(); 
                                     state.video_state.paused.store(false, Ordering::Release);
@@ -427,37 +1065,29 @@ fn main() -> ! {
                                     }
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Confirm?".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Playing media!".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
-                                    let draw_tx1 = draw_tx.clone();
-                                    let file_details1 = modal_state.file.clone();
-                                    let paused1 = state.video_state.paused.clone();
-                                    let current_frame1 = state.video_state.current_frame.clone();
-                                    thread::spawn(move || {
-                                        play_video(current_frame1, paused1, file_details1, draw_tx1);
-                                    });
+                                    record_played(&mut state.nav_state, &state.video_state, &modal_state.file);
+                                    start_playback(&state.video_state, modal_state.file.clone(), &draw_tx);
                                 }
-                                modal_state.selected = 0;
+                                modal_state.modal.selected = 0;
                             }
                         }
                         ButtonEvent::Up => {
                             if let Some(modal_state) = &mut state.modal_state {
-                                modal_state.selected = 0;
-                                draw_tx.send(DrawCommand::SelectNo).unwrap();
+                                modal_state.modal.move_selection(false, &state.theme);
+                                draw_tx.send(DrawCommand::ModalUpdate { modal: modal_state.modal.clone() }).unwrap();
                             }
                         }
                         ButtonEvent::Down => {
                             if let Some(modal_state) = &mut state.modal_state {
-                                modal_state.selected = 1;
-                                draw_tx.send(DrawCommand::SelectYes).unwrap();
-                            }
-                        }
-                        ButtonEvent::TimeChanged => {
-                            let new_current_local_time: DateTime<Local> = Local::now();
-                            {
-                                let mut current_time = state.current_time.lock().unwrap();
-                                *current_time = new_current_local_time;
+                                modal_state.modal.move_selection(true, &state.theme);
+                                draw_tx.send(DrawCommand::ModalUpdate { modal: modal_state.modal.clone() }).unwrap();
                             }
                         }
                         ButtonEvent::CurrentFrameChanged => {}
+                        ButtonEvent::SlideshowTick => {}
+                        // handled before this match
+                        ButtonEvent::TimeChanged | ButtonEvent::DirChanged | ButtonEvent::UsbChanged | ButtonEvent::LibraryChanged | ButtonEvent::LongUp | ButtonEvent::LongDown | ButtonEvent::LongSelect | ButtonEvent::LongEscape => {}
+                        ButtonEvent::TransferProgress | ButtonEvent::TransferDone | ButtonEvent::ImageDecoded => {}
                     }
                 }
                 DisplayState::PlayingSomething => {
@@ -473,39 +1103,45 @@ fn main() -> ! {
                             // there should always be a modal state at this point (file is set during
                             // confirmmediaselection)
                             if let Some(modal_state) = &mut state.modal_state {
-                                modal_state.selected = 0;
+                                modal_state.modal = modal::Modal::new("Exit to navigation menu?", &["No!", "Yes!"]);
+                                draw_tx.send(DrawCommand::ConfirmingBackground { modal: modal_state.modal.clone() }).unwrap();
                             }
-                            draw_tx.send(DrawCommand::ConfirmingBackground { message: format!("Exit to navigation menu?"), options: vec!["No!".to_string(), "Yes!".to_string()] }).unwrap();
                             draw_tx.send(DrawCommand::DrawI2CText { content: "Playing media!".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                             draw_tx.send(DrawCommand::DrawI2CText { content: "Exit media?".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
                         }
                         ButtonEvent::Select => {
-                            // pause media
+                            // pause or resume media
+                            if state.video_state.paused.load(Ordering::Acquire) {
+                                state.video_state.paused.store(false, Ordering::Release);
+                                if let Some(modal_state) = &state.modal_state {
+                                    start_playback(&state.video_state, modal_state.file.clone(), &draw_tx);
+                                }
+                            }
+                            else {
+                                // video thread stops on its own once it sees paused
+                                state.video_state.paused.store(true, Ordering::Release);
+                            }
+                            draw_tx.send(DrawCommand::ShowHud { video_state: state.video_state.clone() }).unwrap();
                         }
                         ButtonEvent::Up => {
                             // turn up volume
                             let volume = state.video_state.volume.load(Ordering::Relaxed);
                             if volume != 100 {
                                 draw_tx.send(DrawCommand::DrawI2CText { content: format!("{}%",state.video_state.volume.load(Ordering::Relaxed).to_string()), position: draw::TOP_VOLUME_VALUE_COORDS, undraw: true, screen: true }).unwrap();
-                                state.video_state.volume.fetch_add(5, Ordering::Relaxed);
+                                state.video_state.volume.store((volume + state.config.volume_step).min(100), Ordering::Relaxed);
                                 draw_tx.send(DrawCommand::DrawI2CText { content: format!("{}%",state.video_state.volume.load(Ordering::Relaxed).to_string()), position: draw::TOP_VOLUME_VALUE_COORDS, undraw: false, screen: true }).unwrap();
                             }
+                            draw_tx.send(DrawCommand::ShowHud { video_state: state.video_state.clone() }).unwrap();
                         }
                         ButtonEvent::Down => {
                             // turn down volume
                             let volume = state.video_state.volume.load(Ordering::Relaxed);
                             if volume != 0 {
                                 draw_tx.send(DrawCommand::DrawI2CText { content: format!("{}%",state.video_state.volume.load(Ordering::Relaxed).to_string()), position: draw::TOP_VOLUME_VALUE_COORDS, undraw: true, screen: true }).unwrap();
-                                state.video_state.volume.fetch_sub(5, Ordering::Relaxed);
+                                state.video_state.volume.store(volume.saturating_sub(state.config.volume_step), Ordering::Relaxed);
                                 draw_tx.send(DrawCommand::DrawI2CText { content: format!("{}%",state.video_state.volume.load(Ordering::Relaxed).to_string()), position: draw::TOP_VOLUME_VALUE_COORDS, undraw: false, screen: true }).unwrap();
                             }
-                        }
-                        ButtonEvent::TimeChanged => {
-                            let new_current_local_time: DateTime<Local> = Local::now();
-                            {
-                                let mut current_time = state.current_time.lock().unwrap();
-                                *current_time = new_current_local_time;
-                            }
+                            draw_tx.send(DrawCommand::ShowHud { video_state: state.video_state.clone() }).unwrap();
                         }
                         ButtonEvent::CurrentFrameChanged => {
                             // draw timestamp to i2c display 2 at point 
@@ -521,6 +1157,10 @@ fn main() -> ! {
                             // draw
                             draw_tx.send(DrawCommand::DrawI2CText { content: new_timestamp, position: draw::TOP_MEDIA_TIMESTAMP_COORDS, undraw: false, screen: true }).unwrap();
                         }
+                        ButtonEvent::SlideshowTick => {}
+                        // handled before this match
+                        ButtonEvent::TimeChanged | ButtonEvent::DirChanged | ButtonEvent::UsbChanged | ButtonEvent::LibraryChanged | ButtonEvent::LongUp | ButtonEvent::LongDown | ButtonEvent::LongSelect | ButtonEvent::LongEscape => {}
+                        ButtonEvent::TransferProgress | ButtonEvent::TransferDone | ButtonEvent::ImageDecoded => {}
                     }
                 }
                 DisplayState::ConfirmingMediaExit => {
@@ -537,25 +1177,19 @@ fn main() -> ! {
                                     // *paused = false;
                                     *current_state = DisplayState::PlayingSomething;
                                 }
-                                modal_state.selected = 0;
+                                modal_state.modal.selected = 0;
                                 draw_tx.send(DrawCommand::DrawI2CText { content: "Exit media?".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                                 draw_tx.send(DrawCommand::DrawI2CText { content: "Playing media!".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
 
-                                let draw_tx1 = draw_tx.clone();
-                                let file_details1 = modal_state.file.clone();
-                                let paused1 = state.video_state.paused.clone();
-                                let current_frame1 = state.video_state.current_frame.clone();
-                                thread::spawn(move || {
-                                    play_video(current_frame1, paused1, file_details1, draw_tx1);
-                                });
-                                // draw_tx.send(DrawCommand::NavigatingBackground { current_dir: state.nav_state.current_dir.clone(), file_count: state.nav_state.file_count, current_index: state.nav_state.current_index }).unwrap();
+                                start_playback(&state.video_state, modal_state.file.clone(), &draw_tx);
+                                // draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
                             }
                         }
                         ButtonEvent::Select => {
                             println!("pressing select in exit!");
                             // set current state to either navigating or playingmedia
                             if let Some(modal_state) = &mut state.modal_state {
-                                if modal_state.selected == 0 {
+                                if modal_state.modal.selected == 0 {
                                     // resume video TODO!()
                                     {
                                         let current_state = state.current_state.clone();
@@ -564,30 +1198,27 @@ fn main() -> ! {
                                         // *paused = false;
                                         *current_state = DisplayState::PlayingSomething;
                                     }
-                                    modal_state.selected = 0;
+                                    modal_state.modal.selected = 0;
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Exit media?".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Playing media!".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
 
-                                    let draw_tx1 = draw_tx.clone();
-                                    let file_details1 = modal_state.file.clone();
-                                    let paused1 = state.video_state.paused.clone();
-                                    let current_frame1 = state.video_state.current_frame.clone();
-                                    thread::spawn(move || {
-                                        play_video(current_frame1, paused1, file_details1, draw_tx1);
-                                    });
-                                    // draw_tx.send(DrawCommand::NavigatingBackground { current_dir: state.nav_state.current_dir.clone(), file_count: state.nav_state.file_count, current_index: state.nav_state.current_index }).unwrap();
+                                    start_playback(&state.video_state, modal_state.file.clone(), &draw_tx);
+                                    // draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
                                 }
-                                else if modal_state.selected == 1 {
+                                else if modal_state.modal.selected == 1 {
                                     // go back to navigation
+                                    record_played(&mut state.nav_state, &state.video_state, &modal_state.file);
+                                    record_watched(&state.nav_state, &state.video_state, &modal_state.file);
+                                    show_toast(&draw_tx, "Bookmark saved", toast::ToastKind::Info);
                                     {
                                         let current_state = state.current_state.clone();
                                         let mut current_state = current_state.lock().unwrap();
                                         *current_state = DisplayState::Navigating;
                                     }
-                                    modal_state.selected = 0;
+                                    modal_state.modal.selected = 0;
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Exit media?".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                                     draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
-                                    draw_tx.send(DrawCommand::NavigatingBackground { current_dir: state.nav_state.current_dir.clone(), file_count: state.nav_state.file_count, current_index: state.nav_state.current_index }).unwrap();
+                                    draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
                                 }
                             }
                         }
@@ -595,23 +1226,16 @@ fn main() -> ! {
                             println!("pressing up in exit!");
                             // invert state
                             if let Some(modal_state) = &mut state.modal_state {
-                                modal_state.selected = 0;
-                                draw_tx.send(DrawCommand::SelectNo).unwrap();
+                                modal_state.modal.move_selection(false, &state.theme);
+                                draw_tx.send(DrawCommand::ModalUpdate { modal: modal_state.modal.clone() }).unwrap();
                             }
                         }
                         ButtonEvent::Down => {
                             println!("pressing down in exit!");
                             // invert state
                             if let Some(modal_state) = &mut state.modal_state {
-                                modal_state.selected = 1;
-                                draw_tx.send(DrawCommand::SelectYes).unwrap();
-                            }
-                        }
-                        ButtonEvent::TimeChanged => {
-                            let new_current_local_time: DateTime<Local> = Local::now();
-                            {
-                                let mut current_time = state.current_time.lock().unwrap();
-                                *current_time = new_current_local_time;
+                                modal_state.modal.move_selection(true, &state.theme);
+                                draw_tx.send(DrawCommand::ModalUpdate { modal: modal_state.modal.clone() }).unwrap();
                             }
                         }
                         ButtonEvent::CurrentFrameChanged => {
@@ -621,37 +1245,129 @@ fn main() -> ! {
                         _ => ()
                     }
                 }
-                DisplayState::ErrorMessage => {
+                DisplayState::ViewingImage => {
                     match event {
                         ButtonEvent::Escape => {
-                            // go back to navigating
+                            // back to navigating
                             {
                                 let current_state = state.current_state.clone();
                                 let mut current_state = current_state.lock().unwrap();
                                 *current_state = DisplayState::Navigating;
                             }
-                            // set modal state to none here if u want idk
-                            draw_tx.send(DrawCommand::DrawI2CText { content: "Error! x_x".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: format!("Img {}", state.image_state.mode.label()), position: Point::zero(), undraw: true, screen: false }).unwrap();
                             draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
-                            draw_tx.send(DrawCommand::NavigatingBackground { current_dir: state.nav_state.current_dir.clone(), file_count: state.nav_state.file_count, current_index: state.nav_state.current_index }).unwrap();
+                            state.image_state.close();
+                            draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
                         }
                         ButtonEvent::Select => {
-                            // go back to navigating
+                            // fit -> zoom 2x -> zoom 4x -> slideshow -> fit
+                            draw_tx.send(DrawCommand::DrawI2CText { content: format!("Img {}", state.image_state.mode.label()), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                            state.image_state.cycle_mode();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: format!("Img {}", state.image_state.mode.label()), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::RawFrame { data: state.image_state.render(&state.theme) }).unwrap();
+                        }
+                        ButtonEvent::Up | ButtonEvent::Down => {
+                            // pan when zoomed, otherwise prev/next image in the folder
+                            let previous_label = format!("Img {}", state.image_state.mode.label());
+                            match state.image_state.step(event == ButtonEvent::Down) {
+                                Ok(()) => {
+                                    println!("viewing image: {}", state.image_state.current_name());
+                                    draw_tx.send(DrawCommand::RawFrame { data: state.image_state.render(&state.theme) }).unwrap();
+                                }
+                                Err(err_msg) => {
+                                    state.image_state.close();
+                                    show_error(&mut state, &draw_tx, err_msg, &previous_label);
+                                }
+                            }
+                        }
+                        ButtonEvent::ImageDecoded => {
+                            let label = format!("Img {}", state.image_state.mode.label());
+                            match state.image_state.take_decoded() {
+                                Some(Ok(())) => {
+                                    draw_tx.send(DrawCommand::RawFrame { data: state.image_state.render(&state.theme) }).unwrap();
+                                }
+                                Some(Err(err_msg)) => {
+                                    state.image_state.close();
+                                    show_error(&mut state, &draw_tx, err_msg, &label);
+                                }
+                                // flipped past it already
+                                None => {}
+                            }
+                        }
+                        ButtonEvent::SlideshowTick if state.image_state.mode == image_viewer::ViewMode::Slideshow => {
+                            let previous_label = format!("Img {}", state.image_state.mode.label());
+                            match state.image_state.next_image() {
+                                Ok(()) => {
+                                    draw_tx.send(DrawCommand::RawFrame { data: state.image_state.render(&state.theme) }).unwrap();
+                                }
+                                Err(err_msg) => {
+                                    state.image_state.close();
+                                    show_error(&mut state, &draw_tx, err_msg, &previous_label);
+                                }
+                            }
+                        }
+                        _ => ()
+                    }
+                }
+                DisplayState::ReadingText => {
+                    match event {
+                        ButtonEvent::Escape => {
+                            // remember position, back to navigating
+                            state.reader_state.close();
                             {
                                 let current_state = state.current_state.clone();
                                 let mut current_state = current_state.lock().unwrap();
                                 *current_state = DisplayState::Navigating;
                             }
-                            // set modal state to none here if u want idk
-                            draw_tx.send(DrawCommand::DrawI2CText { content: "Error! x_x".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.reader_state.drawn_position.clone(), position: draw::SECOND_LINE_COORDS, undraw: true, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: "Reading".to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
                             draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
-                            draw_tx.send(DrawCommand::NavigatingBackground { current_dir: state.nav_state.current_dir.clone(), file_count: state.nav_state.file_count, current_index: state.nav_state.current_index }).unwrap();
+                            draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
+                        }
+                        ButtonEvent::Select => {
+                            // swap between line and page scrolling
+                            state.reader_state.toggle_step();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.reader_state.drawn_position.clone(), position: draw::SECOND_LINE_COORDS, undraw: true, screen: false }).unwrap();
+                            state.reader_state.drawn_position = state.reader_state.position();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.reader_state.drawn_position.clone(), position: draw::SECOND_LINE_COORDS, undraw: false, screen: false }).unwrap();
+                        }
+                        ButtonEvent::Up | ButtonEvent::Down if state.reader_state.scroll(event == ButtonEvent::Down) => {
+                            draw_tx.send(DrawCommand::RawFrame { data: state.reader_state.render(&state.theme) }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.reader_state.drawn_position.clone(), position: draw::SECOND_LINE_COORDS, undraw: true, screen: false }).unwrap();
+                            state.reader_state.drawn_position = state.reader_state.position();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.reader_state.drawn_position.clone(), position: draw::SECOND_LINE_COORDS, undraw: false, screen: false }).unwrap();
                         }
-                        ButtonEvent::TimeChanged => {
-                            let new_current_local_time: DateTime<Local> = Local::now();
+                        _ => ()
+                    }
+                }
+                DisplayState::InspectingHex => {
+                    match event {
+                        ButtonEvent::Escape => {
+                            // back to navigating
                             {
-                                let mut current_time = state.current_time.lock().unwrap();
-                                *current_time = new_current_local_time;
+                                let current_state = state.current_state.clone();
+                                let mut current_state = current_state.lock().unwrap();
+                                *current_state = DisplayState::Navigating;
+                            }
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.hex_state.magic.clone(), position: draw::SECOND_LINE_COORDS, undraw: true, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: state.hex_state.title(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+                            draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
+                        }
+                        ButtonEvent::Select => {
+                            // swap between line and page scrolling
+                            state.hex_state.toggle_step();
+                        }
+                        ButtonEvent::Up | ButtonEvent::Down if state.hex_state.scroll(event == ButtonEvent::Down) => {
+                            match state.hex_state.render(&state.theme) {
+                                Ok(frame) => {
+                                    draw_tx.send(DrawCommand::RawFrame { data: frame }).unwrap();
+                                }
+                                Err(err_msg) => {
+                                    draw_tx.send(DrawCommand::DrawI2CText { content: state.hex_state.magic.clone(), position: draw::SECOND_LINE_COORDS, undraw: true, screen: false }).unwrap();
+                                    let title = state.hex_state.title();
+                                    show_error(&mut state, &draw_tx, err_msg, &title);
+                                }
                             }
                         }
                         _ => ()
@@ -664,11 +1380,10 @@ fn main() -> ! {
                             println!("Shutting down service!");
                             std::process::exit(0);
                         }
-                        ButtonEvent::TimeChanged => {
-                            let new_current_local_time: DateTime<Local> = Local::now();
-                            {
-                                let mut current_time = state.current_time.lock().unwrap();
-                                *current_time = new_current_local_time;
+                        ButtonEvent::Up | ButtonEvent::Down => {
+                            if let Some(modal_state) = &mut state.modal_state {
+                                modal_state.modal.move_selection(event == ButtonEvent::Down, &state.theme);
+                                draw_tx.send(DrawCommand::ModalUpdate { modal: modal_state.modal.clone() }).unwrap();
                             }
                         }
                         _ => ()
@@ -679,9 +1394,52 @@ fn main() -> ! {
     }
 }
 
-fn button_task(chip_path: &str, gpio_number: u32, tx: mpsc::Sender<ButtonEvent>, event_type: ButtonEvent) {
+// puts the video at the top of the recent list w/ how far in it is
+fn record_played(nav_state: &mut NavigatingData, video_state: &PlayingSomethingData, file: &Option<FileDetails>) {
+    if let Some(file) = file {
+        let total_frames = video_state.total_frames.load(Ordering::Relaxed) / PIXELS_PER_FRAME as u64;
+        nav_state.lists.record_played(&file.file_path, video_state.current_frame.load(Ordering::Relaxed), total_frames);
+    }
+}
+// on the way out of a video, only counts if it got close enough to the end
+fn record_watched(nav_state: &NavigatingData, video_state: &PlayingSomethingData, file: &Option<FileDetails>) {
+    let total_frames = video_state.total_frames.load(Ordering::Relaxed) / PIXELS_PER_FRAME as u64;
+    let percent = (video_state.current_frame.load(Ordering::Relaxed) * 100).checked_div(total_frames).unwrap_or(0);
+    if let Some(file) = file && percent >= WATCHED_PERCENT {
+        nav_state.indexer.library.lock().unwrap().mark_watched(&file.file_path);
+    }
+}
+fn format_clock(time: &DateTime<Local>, clock_24h: bool) -> String {
+    time.format(if clock_24h { "%H:%M" } else { "%-I:%M%P" }).to_string()
+}
+// new time for the top nav
+fn redraw_clock(state: &State, draw_tx: &mpsc::Sender<DrawCommand>) {
+    let new_current_local_time: DateTime<Local> = Local::now();
+    let new_formatted_local_time = format_clock(&new_current_local_time, state.config.clock_24h);
+    {
+        let mut current_time = state.current_time.lock().unwrap();
+        *current_time = new_current_local_time;
+    }
+    draw_tx.send(DrawCommand::Clock { time: new_formatted_local_time }).unwrap();
+}
+// back to the navigator (unless we're already there) w/ the error as a toast, i2c_label is whatever's on the 1st small screen
+fn show_error(state: &mut State, draw_tx: &mpsc::Sender<DrawCommand>, err_msg: String, i2c_label: &str) {
+    let navigating = matches!(*state.current_state.lock().unwrap(), DisplayState::Navigating);
+    if !navigating {
+        return_to_navigator(state, draw_tx, i2c_label);
+    }
+    show_toast(draw_tx, &err_msg, toast::ToastKind::Error);
+}
+fn show_toast(draw_tx: &mpsc::Sender<DrawCommand>, message: &str, kind: toast::ToastKind) {
+    draw_tx.send(DrawCommand::Toast { message: message.to_string(), kind }).unwrap();
+}
+// event_type on press, or if long_event is set and the current state uses it: event_type on release, long_event once it's been held for LONG_PRESS
+fn button_task(chip_path: &str, gpio_number: u32, tx: mpsc::Sender<ButtonEvent>, current_state: Arc<Mutex<DisplayState>>, event_type: ButtonEvent, long_event: Option<ButtonEvent>) {
     let mut chip = Chip::new(chip_path).unwrap();
     let mut db = debounce_4(false);
+    // None while released
+    let mut pressed_at: Option<Instant> = None;
+    let mut long_sent = false;
 
     loop {
         let pressed = {
@@ -694,24 +1452,34 @@ fn button_task(chip_path: &str, gpio_number: u32, tx: mpsc::Sender<ButtonEvent>,
             pin.is_low().unwrap()
         };
 
-        if let Some(edge) = db.update(pressed) {
-            if edge == Edge::Rising {
+        match db.update(pressed) {
+            Some(Edge::Rising) => {
+                if long_event.is_some() && current_state.lock().unwrap().uses_long_press() {
+                    pressed_at = Some(Instant::now());
+                    long_sent = false;
+                }
+                else {
+                    tx.send(event_type).unwrap();
+                }
+            }
+            // let go before it counted as a long press
+            Some(Edge::Falling) if pressed_at.take().is_some() && !long_sent => {
                 tx.send(event_type).unwrap();
             }
+            _ => (),
+        }
+        if let (Some(long_event), Some(pressed_at)) = (long_event, pressed_at) && !long_sent && pressed_at.elapsed() >= LONG_PRESS {
+            tx.send(long_event).unwrap();
+            long_sent = true;
         }
         thread::sleep(Duration::from_millis(10));
     }
 }
-fn current_time_task(tx: mpsc::Sender<ButtonEvent>, state: Arc<Mutex<DateTime<Local>>>, current_state: Arc<Mutex<DisplayState>>) {
+fn current_time_task(tx: mpsc::Sender<ButtonEvent>, state: Arc<Mutex<DateTime<Local>>>) {
     loop {
-        match *current_state.lock().unwrap() {
-            DisplayState::Navigating => {
-                let new_current_local_time: DateTime<Local> = Local::now();
-                if new_current_local_time != *state.lock().unwrap() {
-                    tx.send(ButtonEvent::TimeChanged).unwrap();
-                }
-            }
-            _ => ()
+        let new_current_local_time: DateTime<Local> = Local::now();
+        if new_current_local_time != *state.lock().unwrap() {
+            tx.send(ButtonEvent::TimeChanged).unwrap();
         }
         thread::sleep(Duration::from_secs(1));
     }
@@ -778,23 +1546,24 @@ impl <'a> DrawTarget for FramebufferDisplay<'a> {
 enum DrawCommand {
     // happens after selecting or exiting
     ConfirmingBackground {
-        message: String,
-        options: Vec<String>
+        modal: modal::Modal,
     },
-    // draw rect on top, 3 rects in middle/bottom for file icon, and file name after state change
-    // to navigating
+    // whole navigator after state change to navigating
     NavigatingBackground {
-        current_dir: PathBuf,
-        file_count: usize,
+        dir: dir_model::DirModel,
         current_index: usize,
     },
-    // text only happens when navigatingbackground has been sent and state has changed to
-    // navigating
-    Text {
-        content: String,
-        position: Point,
-        undraw: bool,
-        is_selected: bool,
+    // scrolled or changed dir while navigating, only what changed gets redrawn
+    NavigatingUpdate {
+        view: Box<nav_panel::NavView>,
+        transition: nav_panel::Transition,
+    },
+    // top nav, kept for when the navigator is shown again
+    Clock {
+        time: String,
+    },
+    Weather {
+        weather: String,
     },
     RawFrame {
         data: Vec<u8>,
@@ -807,138 +1576,67 @@ enum DrawCommand {
         screen: bool,
     },
     ClearI2CScreen(bool),
-    SelectYes,
-    SelectNo,
-    DrawIcon {
-        point: Point,
-        kind: IconKind,
+    // selection or scroll moved in the modal that's up
+    ModalUpdate {
+        modal: modal::Modal,
+    },
+    // shown if a panel of that kind isn't up already, otherwise only what changed gets redrawn
+    Panel {
+        panel: Panel,
+    },
+    // show the playback hud over the current frame, it fades out on its own
+    ShowHud {
+        video_state: PlayingSomethingData,
+    },
+    // thumbnail beside the selected carousel entry
+    Thumbnail {
+        data: Vec<u8>,
         undraw: bool,
+    },
+    // over whatever's showing for a few seconds, replaces one that's already up
+    Toast {
+        message: String,
+        kind: toast::ToastKind,
+    },
+    // whatever's showing gets redrawn in it
+    Theme {
+        theme: theme::Theme,
+    },
+    // percent, from the config
+    Brightness {
+        percent: u8,
+    },
+}
+// full screen views kept on a retained screen in the draw task, like the modal
+#[derive(Clone, Debug)]
+enum Panel {
+    Keyboard(keyboard::Keyboard),
+    Transfer(file_ops::Progress),
+}
+impl Panel {
+    fn screen(&self) -> widgets::Screen {
+        match self {
+            Panel::Keyboard(keyboard) => keyboard.screen(),
+            Panel::Transfer(progress) => progress.screen(),
+        }
+    }
+    // screen has to be from a panel of the same kind
+    fn update(&self, screen: &mut widgets::Screen) {
+        match self {
+            Panel::Keyboard(keyboard) => keyboard.update(screen),
+            Panel::Transfer(progress) => progress.update(screen),
+        }
     }
 }
+#[derive(Clone, Copy, PartialEq, Debug)]
 enum IconKind {
     Txt,
     Video,
     Folder,
+    Image,
+    Usb,
     Questionmark
 }
-// light background, dark text
-fn draw_modal(fb: &mut [u8], width: usize, height: usize, msg: &str, options: Vec<String>) {
-    
-    clear_screen(fb);
-
-    // add error msg, line break every 30 characters, map options on bottom spaced based on len
-    let mut display = FramebufferDisplay { buf: fb, width, height };
-    let modal_style = PrimitiveStyleBuilder::new()
-        .stroke_width(2)
-        .stroke_color(Rgb565::WHITE)
-        .build();
-
-    // border of modal
-    RoundedRectangle::with_equal_corners(
-        Rectangle::new(Point::new(40, 40), Size::new(240, 160)),
-        Size::new(10, 10),
-    )
-    .into_styled(modal_style)
-    .draw(&mut display)
-    .unwrap();
-
-    let mut formatted_msg = String::new();
-    for (index, character) in msg.chars().enumerate() {
-        formatted_msg += character.to_string().as_str();
-        if (index + 1) % 30 == 0 && (index + 1) != msg.len() {
-            formatted_msg += "\n";
-        }
-    }
-
-    // parse msg, break it every 25 chars
-    let txt_style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE);
-    Text::with_baseline(formatted_msg.as_str(), Point::new(60, 60), txt_style, Baseline::Top)
-        .draw(&mut display)
-        .unwrap();
-
-    // options
-    let option_style = PrimitiveStyleBuilder::new()
-        .stroke_width(1)
-        .stroke_color(Rgb565::CSS_SKY_BLUE)
-        .build();
-
-    // 6 padding on left, 4 on top
-    let num_of_options = options.iter().count();
-    if num_of_options == 1 {
-        // "okay" option
-        RoundedRectangle::with_equal_corners(
-            Rectangle::new(Point::new(140, 160), Size::new(40, 20)),
-            Size::new(6, 6),
-        )
-        .into_styled(option_style)
-        .draw(&mut display)
-        .unwrap();
-        Text::with_baseline("Okay!", Point::new(146, 164), txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-    }
-    else if num_of_options == 2 {
-        // no option
-        Rectangle::new(draw::MODAL_NO_BORDER_COORDS, Size::new(40, 20))
-            .into_styled(option_style)
-            .draw(&mut display)
-            .unwrap();
-        Text::with_baseline("No!", Point::new(116, 164), txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-
-        // yes option
-        Text::with_baseline("Yes!", Point::new(176, 164), txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-    }
-    // else if num_of_options == 3 {
-    //
-    // }
-    // else {
-    //
-    // }
-}
-fn select_yes(fb: &mut [u8], width: usize, height: usize) {
-    let mut display = FramebufferDisplay { buf: fb, width, height };
-    let selected_style = PrimitiveStyleBuilder::new()
-        .stroke_width(1)
-        .stroke_color(Rgb565::CSS_SKY_BLUE)
-        .build();
-    let clear_style = PrimitiveStyleBuilder::new()
-        .stroke_width(1)
-        .stroke_color(Rgb565::CSS_DARK_GRAY)
-        .build();
-
-    Rectangle::new(draw::MODAL_YES_BORDER_COORDS, Size::new(40, 20))
-        .into_styled(selected_style)
-        .draw(&mut display)
-        .unwrap();
-    Rectangle::new(draw::MODAL_NO_BORDER_COORDS, Size::new(40, 20))
-        .into_styled(clear_style)
-        .draw(&mut display)
-        .unwrap();
-}
-fn select_no(fb: &mut [u8], width: usize, height: usize) {
-    let mut display = FramebufferDisplay { buf: fb, width, height };
-    let selected_style = PrimitiveStyleBuilder::new()
-        .stroke_width(1)
-        .stroke_color(Rgb565::CSS_SKY_BLUE)
-        .build();
-    let clear_style = PrimitiveStyleBuilder::new()
-        .stroke_width(1)
-        .stroke_color(Rgb565::CSS_DARK_GRAY)
-        .build();
-
-    Rectangle::new(draw::MODAL_YES_BORDER_COORDS, Size::new(40, 20))
-        .into_styled(clear_style)
-        .draw(&mut display)
-        .unwrap();
-    Rectangle::new(draw::MODAL_NO_BORDER_COORDS, Size::new(40, 20))
-        .into_styled(selected_style)
-        .draw(&mut display)
-        .unwrap();
-}
 // fn undraw_modal(fb: &mut [u8], width: usize, height: usize, msg: &str) {
 //     let mut display = FramebufferDisplay { buf: fb, width, height };
 //
@@ -953,211 +1651,72 @@ fn select_no(fb: &mut [u8], width: usize, height: usize) {
 //         .draw(&mut display)
 //         .unwrap();
 // }
-// top rect, and 3 middle rects spaced out by 10px
-fn draw_nav_background(fb: &mut [u8], width: usize, height: usize, current_dir: PathBuf, file_count: usize, current_index: usize) {
-    // wipe screen first TODO()
-    clear_screen(fb);
-
-    // draw when transitioning to navigating state
-    let mut display = FramebufferDisplay { buf: fb, width, height };
-
-    // top nav rectangle
-    let nav_style = PrimitiveStyleBuilder::new()
-        .stroke_width(1)
-        .stroke_color(Rgb565::WHITE)
-        .build();
-
-    let selected_style = PrimitiveStyleBuilder::new()
-        .stroke_width(2)
-        .stroke_color(Rgb565::CSS_SKY_BLUE)
-        .build();
-
-    let carousel_style = PrimitiveStyleBuilder::new()
-        .stroke_width(2)
-        .stroke_color(Rgb565::CSS_LIGHT_BLUE)
-        .build();
-
-    // top meta
-    RoundedRectangle::with_equal_corners(
-        Rectangle::new(draw::TOP_NAV_BG_COORDS, Size::new(300, 40)),
-        Size::new(10, 10),
-    )
-    .into_styled(nav_style)
-    .draw(&mut display)
-    .unwrap();
-
-    // carousel 
-    RoundedRectangle::with_equal_corners(
-        Rectangle::new(draw::CAROUSEL_CONTAINER_BG_COORDS, Size::new(270, 140)),
-        Size::new(12, 12),
-    )
-    .into_styled(carousel_style)
-    .draw(&mut display)
-    .unwrap();
-
-    // selected item in carousel
-    RoundedRectangle::with_equal_corners(
-        Rectangle::new(draw::MIDDLE_CAROUSEL_BG_COORDS, Size::new(250, 40)),
-        Size::new(12, 12),
-    )
-    .into_styled(selected_style)
-    .draw(&mut display)
-    .unwrap();
-
-    // add nav images on top. (folder, temperature, time icons)
-    draw::draw_folder(&mut display, width, height, Point::new(14, 10));
-    draw::draw_cloud(&mut display, width, height, Point::new(150, 10));
-    draw::draw_clock(&mut display, width, height, Point::new(220, 10));
-
-    // current path, file_count, and current file navigated on (index of files of dir)
-    let txt_style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE);
-
-    // current_dir
-    Text::with_baseline(format_dir(current_dir.to_owned()).as_str(), draw::TOP_NAV_PATH_COORDS, txt_style, Baseline::Top)
-        .draw(&mut display)
-        .unwrap();
-
-    // file_count
-    if file_count == 0 {
-        Text::with_baseline(format!("0/{}", file_count).as_str(), draw::TOP_NAV_FILE_INDEX_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
+fn determine_icon_to_draw(path: &Path, is_dir: bool) -> IconKind {
+    if is_dir {
+        // draw folder_icon
+        IconKind::Folder
     }
     else {
-        Text::with_baseline(format!("1/{}", file_count).as_str(), draw::TOP_NAV_FILE_INDEX_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-    }
-
-    // get idx, idx - 1, and idx + 1, if exists, draw in appropriate spots.
-    let readdir: Vec<_> = std::fs::read_dir(current_dir.to_owned()).unwrap().collect::<Result<_, _>>().unwrap();
-    let idx_minus_one = { if current_index == 0 { None } else { readdir.get(current_index - 1) } };
-    let current_idx = readdir.get(current_index);
-    let idx_plus_one = readdir.get(current_index + 1);
-
-
-    if let Some(idx_minus_one) = idx_minus_one {
-        let kind = determine_icon_to_draw(idx_minus_one);
-        draw_icon(TOP_CAROUSEL_ICON_COORDS, &mut display, kind);
-        Text::with_baseline(idx_minus_one.file_name().to_str().unwrap(), draw::TOP_CAROUSEL_TXT_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-    }
-    if let Some(current_idx) = current_idx {
-        let kind = determine_icon_to_draw(current_idx);
-        draw_icon(MIDDLE_CAROUSEL_ICON_COORDS, &mut display, kind);
-        // file size, 
-        Text::with_baseline("Size", draw::ENTRY_META_FILESIZE_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-        Text::with_baseline(&utils::format_bytes(current_idx.metadata().unwrap().size()), draw::ENTRY_META_FILESIZE_TEXT_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-
-        // last_modified
-        let mtime = current_idx.metadata().unwrap().mtime();
-        let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-        let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-        Text::with_baseline("Last modified", draw::ENTRY_META_LASTMODIFIED_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-        Text::with_baseline(&last_modified, draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-        // selected file name
-        Text::with_baseline(current_idx.file_name().to_str().unwrap(), draw::MIDDLE_CAROUSEL_TXT_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-    }
-    if let Some(idx_plus_one) = idx_plus_one {
-        let kind = determine_icon_to_draw(idx_plus_one);
-        draw_icon(BOTTOM_CAROUSEL_ICON_COORDS, &mut display, kind);
-        Text::with_baseline(idx_plus_one.file_name().to_str().unwrap(), draw::BOTTOM_CAROUSEL_TXT_COORDS, txt_style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-    }
-}
-fn determine_icon_to_draw(direntry: &DirEntry) -> IconKind {
-    if let Ok(file_type) = direntry.file_type() {
-        if file_type.is_dir() {
-            // draw folder_icon
-            IconKind::Folder
-        }
-        else {
-            // not a dir, can only be a file with/without an extension. .bashrc doesnt have an ext.
-            if let Some(extension) = direntry.path().extension() {
-                if let Some(ext) = extension.to_str() {
-                    println!("extension: {}", ext);
-                }
-                match  extension.to_str().unwrap() {
-                    "txt" | "bashrc" | "rs" | "sh" => {
-                        // draw txt icon
-                        IconKind::Txt
-                    },
-                    "rgb" | "raw" | "rgb565" | "mp4" => {
-                        // draw video icon
-                        IconKind::Video
-                    },
-                    _ => {
-                        // draw questionmark icon
-                        IconKind::Questionmark
-                    }
+        // not a dir, can only be a file with/without an extension. .bashrc doesnt have an ext.
+        if let Some(extension) = path.extension() {
+            match extension.to_string_lossy().to_lowercase().as_str() {
+                "txt" | "bashrc" | "rs" | "sh" => {
+                    // draw txt icon
+                    IconKind::Txt
+                },
+                "rgb" | "raw" | "rgb565" | "mp4" => {
+                    // draw video icon
+                    IconKind::Video
+                },
+                "png" | "jpg" | "jpeg" | "bmp" => {
+                    // draw camera icon
+                    IconKind::Image
+                },
+                _ => {
+                    // draw questionmark icon
+                    IconKind::Questionmark
                 }
             }
-            else {
-                // draw questionmark icon
-                IconKind::Questionmark
-            }
+        }
+        else if text_reader::is_text(path) {
+            // dotfiles like .bashrc have no extension
+            IconKind::Txt
+        }
+        else {
+            // draw questionmark icon
+            IconKind::Questionmark
         }
     }
-    else {
-        // draw questionmark icon
-        IconKind::Questionmark
-    }
-
 }
-fn draw_icon(point: Point, display: &mut FramebufferDisplay, kind: IconKind) {
+fn draw_icon<D: DrawTarget<Color = Rgb565>>(point: Point, display: &mut D, kind: IconKind, theme: &theme::Theme) where D::Error: std::fmt::Debug {
     // icons
     // each icon is 20widthx24height
     // 20height x 24width x 2
     // 960bytes total
     // .. I think
-    let folder_icon_file = std::fs::read("/home/yassin/cross_compiled/filetype_icons/folder_icon.rgb").unwrap();
-    let video_icon_file = std::fs::read("/home/yassin/cross_compiled/filetype_icons/video_icon.rgb").unwrap();
-    let questionmark_icon_file = std::fs::read("/home/yassin/cross_compiled/filetype_icons/questionmark_icon.rgb").unwrap();
-    let txtfile_icon_file = std::fs::read("/home/yassin/cross_compiled/filetype_icons/txtfile_icon.rgb").unwrap();
-    match kind {
-        IconKind::Folder => {
-            let raw: ImageRawLE<Rgb565> = ImageRaw::new(&folder_icon_file, 24);
-            let image = Image::new(&raw, point);
-            image.draw(display).unwrap();
+    let file_name = match kind {
+        IconKind::Folder => "folder_icon.rgb",
+        IconKind::Video => "video_icon.rgb",
+        IconKind::Txt => "txtfile_icon.rgb",
+        IconKind::Questionmark => "questionmark_icon.rgb",
+        IconKind::Image => {
+            // no .rgb icon for images, camera is drawn w/ primitives
+            draw::draw_camera(display, point, theme.text);
+            return;
         }
-        IconKind::Video => {
-            let raw: ImageRawLE<Rgb565> = ImageRaw::new(&video_icon_file, 24);
-            let image = Image::new(&raw, point);
-            image.draw(display).unwrap();
+        IconKind::Usb => {
+            draw::draw_usb_stick(display, point, theme.text);
+            return;
         }
-        IconKind::Txt => {
-            let raw: ImageRawLE<Rgb565> = ImageRaw::new(&txtfile_icon_file, 24);
-            let image = Image::new(&raw, point);
-            image.draw(display).unwrap();
-        }
-        IconKind::Questionmark => {
-            let raw: ImageRawLE<Rgb565> = ImageRaw::new(&questionmark_icon_file, 24);
-            let image = Image::new(&raw, point);
-            image.draw(display).unwrap();
-        }
-    }
-}
-fn undraw_icon(point: Point, display: &mut FramebufferDisplay) {
-    let style = PrimitiveStyleBuilder::new()
-        .fill_color(Rgb565::CSS_DARK_GRAY)
-        .build();
-
-    Rectangle::new(point, Size::new(24, 20))
-        .into_styled(style)
-        .draw(display)
-        .unwrap();
+    };
+    // from the theme's icon set
+    let Some(icon_file) = theme.icon(file_name) else {
+        println!("no icon called {}", file_name);
+        return;
+    };
+    let raw: ImageRawLE<Rgb565> = ImageRaw::new(&icon_file, 24);
+    let image = Image::new(&raw, point);
+    image.draw(display).unwrap();
 }
 fn undraw_nav_background(fb: &mut [u8], width: usize, height: usize, msg: &str, point: Point) {
     // undraw when leaving navigating state
@@ -1174,36 +1733,11 @@ fn undraw_nav_background(fb: &mut [u8], width: usize, height: usize, msg: &str,
         .unwrap();
 }
 
-fn draw_text(fb: &mut [u8], width: usize, height: usize, msg: &str, point: Point) {
-    let mut display = FramebufferDisplay { buf: fb, width, height };
-    let style = MonoTextStyle::new(&FONT_6X10, Rgb565::WHITE);
-
-    Text::with_baseline(msg, point, style, Baseline::Top)
-        .draw(&mut display)
-        .unwrap();
-}
-fn undraw_text(fb: &mut [u8], width: usize, height: usize, msg: &str, point: Point, is_selected: bool) {
-    let mut display = FramebufferDisplay { buf: fb, width, height };
-    if is_selected {
-        let style = MonoTextStyle::new(&FONT_6X10, Rgb565::CSS_DARK_GRAY);
-
-        Text::with_baseline(msg, point, style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-    }
-    else {
-        let style = MonoTextStyle::new(&FONT_6X10, Rgb565::CSS_DARK_GRAY);
-
-        Text::with_baseline(msg, point, style, Baseline::Top)
-            .draw(&mut display)
-            .unwrap();
-    }
-}
-fn clear_screen(fb: &mut [u8]) {
+fn clear_screen(fb: &mut [u8], theme: &theme::Theme) {
     let mut display = FramebufferDisplay { buf: fb, width: 320, height: 240 };
 
     let style = PrimitiveStyleBuilder::new()
-        .fill_color(Rgb565::CSS_DARK_GRAY)
+        .fill_color(theme.background)
         .build();
     Rectangle::new(Point::zero(), Size::new(320, 240))
         .into_styled(style)
@@ -1215,15 +1749,9 @@ fn draw_raw_frame(fb: &mut [u8], frame_data: &[u8]) {
     fb.copy_from_slice(frame_data);
 }
 fn format_dir(current_dir: PathBuf) -> String {
-    let string = current_dir.to_str().unwrap().to_owned();
-    let mut formatted = string.replace("/yassin", "");
-    if formatted.len() > 40 {
-        formatted.replace_range(40..=formatted.len() - 1, "...");
-        formatted
-    }
-    else {
-        formatted
-    }
+    // lossy so odd bytes in a folder name can't panic. not cut here, the label ellipsizes to whatever fits
+    let string = current_dir.to_string_lossy().into_owned();
+    string.replace("/yassin", "")
 }
 fn start_drawing_task(mut draw_rx: mpsc::Receiver<DrawCommand>) {
     // spi
@@ -1276,32 +1804,131 @@ fn start_drawing_task(mut draw_rx: mpsc::Receiver<DrawCommand>) {
         .unwrap();
     i2c_screen2_display.flush().unwrap();
 
+    // playback hud, composited over the last frame so the frame itself stays untouched
+    let mut active_hud: Option<hud::Hud> = None;
+    let mut last_frame: Vec<u8> = Vec::new();
+    // retained screens, whichever one is showing gets repainted as it changes
+    let mut nav_panel = nav_panel::NavPanel::new();
+    // kept w/ what it's showing so it can be laid out again for a new theme
+    let mut modal: Option<(modal::Modal, widgets::Screen)> = None;
+    // same for the keyboard and transfer progress
+    let mut panel: Option<(Panel, widgets::Screen)> = None;
+    // text on the status line of the 1st small screen when it's too long to sit still, w/ the offset it's drawn at
+    let mut status_marquee: Option<(String, widgets::Marquee, u32)> = None;
+    // the toast that's up and when it goes away, kept so a new theme can draw it again
+    let mut toast: Option<(String, toast::ToastKind, Instant)> = None;
+    // replaced by DrawCommand::Theme, the main loop sends the configured one first thing
+    let mut theme = theme::Theme::default();
+    let mut back = back_buffer::BackBuffer::new(width, height);
+
     loop {
-        while let Ok(cmd) = draw_rx.recv() {
+        // wakes up every frame while the carousel's sliding
+        let tick = if nav_panel.animating() { animation::FRAME } else { hud::HUD_TICK };
+        // or sooner if a toast is due to go
+        let wait = toast.as_ref().map_or(tick, |(_, _, until)| tick.min(until.saturating_duration_since(Instant::now())));
+        let mut next = draw_rx.recv_timeout(wait).ok();
+        if next.is_none() && tick == hud::HUD_TICK {
+            // no frames coming in (paused), so fade the hud out from here
+            if active_hud.is_some() && !last_frame.is_empty() {
+                draw_raw_frame(&mut back.buf, &last_frame);
+                hud::draw_active_hud(&mut back.buf, width, height, &mut active_hud, &theme);
+                back.mark_all();
+            }
+        }
+        while let Some(cmd) = next {
             match cmd {
-                DrawCommand::Text { content, position, undraw, is_selected } => {
-                    if undraw {
-                        undraw_text(&mut mapped, width, height, content.as_str(), position, is_selected);
-                    }
-                    else {
-                        draw_text(&mut mapped, width, height, content.as_str(), position);
-                    }
-                },
-                DrawCommand::ConfirmingBackground { message, options } => {
-                    draw_modal(&mut mapped, width, height, &message, options);
+                DrawCommand::ConfirmingBackground { modal: shown } => {
+                    active_hud = None;
+                    nav_panel.hide();
+                    let mut screen = shown.screen(&theme);
+                    let rects = screen.paint(&mut back.buf, width, height, &theme);
+                    back.mark_rects(rects);
+                    modal = Some((shown, screen));
+                    panel = None;
                 },
         // current dir, 
-                DrawCommand::NavigatingBackground { current_dir, file_count, current_index } => {
-                    draw_nav_background(&mut mapped, width, height, current_dir, file_count, current_index);
+                DrawCommand::NavigatingBackground { dir, current_index } => {
+                    active_hud = None;
+                    modal = None;
+                    panel = None;
+                    nav_panel.update(nav_panel::NavView::new(&dir, current_index), nav_panel::Transition::None);
+                    let rects = nav_panel.show(&mut back.buf, width, height, &theme);
+                    back.mark_rects(rects);
+                }
+                DrawCommand::NavigatingUpdate { view, transition } => {
+                    nav_panel.update(*view, transition);
+                    let rects = nav_panel.paint(&mut back.buf, width, height, &theme);
+                    back.mark_rects(rects);
+                }
+                DrawCommand::Clock { time } => {
+                    nav_panel.set_clock(&time);
+                    let rects = nav_panel.paint(&mut back.buf, width, height, &theme);
+                    back.mark_rects(rects);
+                }
+                DrawCommand::Weather { weather } => {
+                    nav_panel.set_weather(&weather);
+                    let rects = nav_panel.paint(&mut back.buf, width, height, &theme);
+                    back.mark_rects(rects);
                 }
                 DrawCommand::RawFrame { data } => {
-                    draw_raw_frame(&mut mapped, &data);
+                    nav_panel.hide();
+                    modal = None;
+                    panel = None;
+                    draw_raw_frame(&mut back.buf, &data);
+                    hud::draw_active_hud(&mut back.buf, width, height, &mut active_hud, &theme);
+                    back.mark_all();
+                    last_frame = data;
                 },
+                DrawCommand::Brightness { percent } => {
+                    back.set_brightness(percent);
+                }
+                DrawCommand::Theme { theme: new_theme } => {
+                    println!("theme: {}", new_theme.name);
+                    theme = new_theme;
+                    nav_panel.invalidate();
+                    let rects = nav_panel.paint(&mut back.buf, width, height, &theme);
+                    back.mark_rects(rects);
+                    // the font might've changed, so it's laid out again
+                    if let Some((shown, screen)) = &mut modal {
+                        *screen = shown.screen(&theme);
+                        let rects = screen.paint(&mut back.buf, width, height, &theme);
+                        back.mark_rects(rects);
+                    }
+                    if let Some((_, screen)) = &mut panel {
+                        screen.invalidate();
+                        let rects = screen.paint(&mut back.buf, width, height, &theme);
+                        back.mark_rects(rects);
+                    }
+                    if let Some((message, kind, _)) = &toast {
+                        back.set_overlay(Some((toast::BOUNDS, toast::render(message, *kind, &theme))));
+                    }
+                }
                 DrawCommand::ClearScreen => {
-                    clear_screen(&mut mapped);
+                    active_hud = None;
+                    nav_panel.hide();
+                    modal = None;
+                    panel = None;
+                    clear_screen(&mut back.buf, &theme);
+                    back.mark_all();
                 }
                 DrawCommand::DrawI2CText { content, position, undraw, screen } => {
-                    if screen == false {
+                    let too_long = fonts::text_width(&content, &FONT_8X13) > draw::I2C_WIDTH;
+                    if !screen && position == draw::SECOND_LINE_COORDS && (too_long || status_marquee.is_some()) {
+                        // a scrolling status can't be undrawn by drawing it again, so the whole line goes
+                        status_marquee = None;
+                        clear_i2c_line(&mut i2c_screen1_display, position);
+                        if !undraw && too_long {
+                            draw_i2c_marquee(&mut i2c_screen1_display, content.as_str(), position, 0);
+                            status_marquee = Some((content, widgets::Marquee::new(), 0));
+                        }
+                        else if !undraw {
+                            draw_i2c_text(&mut i2c_screen1_display, content.as_str(), position);
+                        }
+                        else {
+                            i2c_screen1_display.flush().unwrap();
+                        }
+                    }
+                    else if !screen {
                         if undraw {
                             undraw_i2c_text(&mut i2c_screen1_display, content.as_str(), position);
                         }
@@ -1320,7 +1947,8 @@ fn start_drawing_task(mut draw_rx: mpsc::Receiver<DrawCommand>) {
                 }
                 DrawCommand::ClearI2CScreen (screen) => {
                     // screen 1
-                    if screen == false {
+                    if !screen {
+                        status_marquee = None;
                         clear_i2c_screen(&mut i2c_screen1_display);
                     }
                     // screen 2
@@ -1328,20 +1956,52 @@ fn start_drawing_task(mut draw_rx: mpsc::Receiver<DrawCommand>) {
                         clear_i2c_screen(&mut i2c_screen2_display);
                     }
                 }
-                DrawCommand::SelectNo => {
-                    select_no(&mut mapped, width, height);
-                }
-                DrawCommand::SelectYes => {
-                    select_yes(&mut mapped, width, height);
+                DrawCommand::ModalUpdate { modal: update } => {
+                    if let Some((shown, screen)) = &mut modal {
+                        update.update(screen, &theme);
+                        *shown = update;
+                        let rects = screen.paint(&mut back.buf, width, height, &theme);
+                        back.mark_rects(rects);
+                    }
                 }
-                DrawCommand::DrawIcon { point, kind, undraw } => {
-                    let mut display = FramebufferDisplay { buf: &mut mapped, width, height };
-                    if undraw {
-                        undraw_icon(point, &mut display);
+                DrawCommand::Panel { panel: update } => {
+                    match &mut panel {
+                        Some((shown, screen)) if std::mem::discriminant(shown) == std::mem::discriminant(&update) => {
+                            update.update(screen);
+                            *shown = update;
+                        }
+                        _ => {
+                            active_hud = None;
+                            nav_panel.hide();
+                            modal = None;
+                            let screen = update.screen();
+                            panel = Some((update, screen));
+                        }
                     }
-                    else {
-                        draw_icon(point, &mut display, kind);
+                    if let Some((_, screen)) = &mut panel {
+                        let rects = screen.paint(&mut back.buf, width, height, &theme);
+                        back.mark_rects(rects);
+                    }
+                }
+                DrawCommand::Thumbnail { data, undraw } => {
+                    nav_panel.set_thumbnail(if undraw { Vec::new() } else { data });
+                    let rects = nav_panel.paint(&mut back.buf, width, height, &theme);
+                    back.mark_rects(rects);
+                }
+                DrawCommand::ShowHud { video_state } => {
+                    nav_panel.hide();
+                    modal = None;
+                    panel = None;
+                    active_hud = Some(hud::Hud::new(video_state));
+                    if !last_frame.is_empty() {
+                        draw_raw_frame(&mut back.buf, &last_frame);
                     }
+                    hud::draw_active_hud(&mut back.buf, width, height, &mut active_hud, &theme);
+                    back.mark_all();
+                }
+                DrawCommand::Toast { message, kind } => {
+                    back.set_overlay(Some((toast::BOUNDS, toast::render(&message, kind, &theme))));
+                    toast = Some((message, kind, Instant::now() + kind.duration()));
                 }
                 _ => ()
                 // DrawCommand::DrawI2CText { content, position, undraw } => {
@@ -1353,7 +2013,24 @@ fn start_drawing_task(mut draw_rx: mpsc::Receiver<DrawCommand>) {
                 //     }
                 // }
             }
+            // whatever else is already queued goes out in the same flush
+            next = draw_rx.try_recv().ok();
+        }
+        // scrolling text and the carousel move on w/ time, not commands
+        let rects = nav_panel.tick(&mut back.buf, width, height, &theme);
+        back.mark_rects(rects);
+        if let Some((text, marquee, drawn_offset)) = &mut status_marquee {
+            let offset = marquee.offset(fonts::text_width(text, &FONT_8X13));
+            if offset != *drawn_offset {
+                *drawn_offset = offset;
+                draw_i2c_marquee(&mut i2c_screen1_display, text.as_str(), draw::SECOND_LINE_COORDS, offset);
+            }
+        }
+        if toast.as_ref().is_some_and(|(_, _, until)| Instant::now() >= *until) {
+            toast = None;
+            back.set_overlay(None);
         }
+        back.flush(&mut mapped);
     }
 }
 
@@ -1362,14 +2039,9 @@ fn draw_i2c_text(
     content: &str,
     point: Point
 ) {
-    let style = MonoTextStyleBuilder::new()
-        .font(&FONT_8X13)
-        .text_color(BinaryColor::On)
-        .build();
-
-    Text::with_baseline(content, point, style, Baseline::Top)
-        .draw(display)
-        .unwrap();
+    // file names can be anything, so not plain Text. cut to the screen, the status line scrolls instead
+    let content = fonts::ellipsize(content, draw::I2C_WIDTH.saturating_sub(point.x as u32), &FONT_8X13);
+    fonts::draw_text(display, &content, point, &FONT_8X13, BinaryColor::On);
     display.flush().unwrap();
 }
 fn undraw_i2c_text(
@@ -1377,14 +2049,32 @@ fn undraw_i2c_text(
     content: &str,
     point: Point,
 ) {
-    let style = MonoTextStyleBuilder::new()
-        .font(&FONT_8X13)
-        .text_color(BinaryColor::Off)
-        .build();
-
-    Text::with_baseline(content, point, style, Baseline::Top)
+    let content = fonts::ellipsize(content, draw::I2C_WIDTH.saturating_sub(point.x as u32), &FONT_8X13);
+    fonts::draw_text(display, &content, point, &FONT_8X13, BinaryColor::Off);
+    display.flush().unwrap();
+}
+// one FONT_8X13 line, full width. not flushed
+fn clear_i2c_line(
+    display: &mut Ssd1306<I2CInterface<I2cdev>, DisplaySize128x32, BufferedGraphicsMode<DisplaySize128x32>>,
+    point: Point,
+) {
+    Rectangle::new(Point::new(0, point.y), Size::new(draw::I2C_WIDTH, FONT_8X13.character_size.height))
+        .into_styled(PrimitiveStyle::with_fill(BinaryColor::Off))
         .draw(display)
         .unwrap();
+}
+// text wider than the screen, scrolled left by offset and wrapping round
+fn draw_i2c_marquee(
+    display: &mut Ssd1306<I2CInterface<I2cdev>, DisplaySize128x32, BufferedGraphicsMode<DisplaySize128x32>>,
+    content: &str,
+    point: Point,
+    offset: u32,
+) {
+    clear_i2c_line(display, point);
+    let scrolled = point - Point::new(offset as i32, 0);
+    let wrapped = scrolled + Point::new((fonts::text_width(content, &FONT_8X13) + widgets::MARQUEE_GAP) as i32, 0);
+    fonts::draw_text(display, content, scrolled, &FONT_8X13, BinaryColor::On);
+    fonts::draw_text(display, content, wrapped, &FONT_8X13, BinaryColor::On);
     display.flush().unwrap();
 }
 fn clear_i2c_screen(display: &mut Ssd1306<I2CInterface<I2cdev>, DisplaySize128x32, BufferedGraphicsMode<DisplaySize128x32>>) {
@@ -1393,128 +2083,114 @@ fn clear_i2c_screen(display: &mut Ssd1306<I2CInterface<I2cdev>, DisplaySize128x3
 }
 
 
-// do nothing len 0/1
-// determine where in iteration u are, so that u can undraw and draw if there is index-1, and index+1/index+2, or vice versa
-// can animate these in future
-fn scroll_up(nav_state: &NavigatingData, draw_tx: mpsc::Sender<DrawCommand>) {
-
-    let readdir: Vec<_> = std::fs::read_dir(nav_state.current_dir.to_owned()).unwrap().collect::<Result<_, _>>().unwrap();
-    let idx_plus_one = readdir.get(nav_state.current_index + 1);
-    let current_idx = readdir.get(nav_state.current_index);
-    let idx_minus_one = { if nav_state.current_index == 0 { None } else { readdir.get(nav_state.current_index - 1) } };
-    let idx_minus_two = { if nav_state.current_index == 0 || nav_state.current_index == 1 { None } else { readdir.get(nav_state.current_index - 2) } };
-
-    // undraw based on indexes available
-    if let Some(idx_minus_one) = idx_minus_one {
-        let kind = determine_icon_to_draw(idx_minus_one);
-        draw_tx.send(DrawCommand::DrawIcon { point: TOP_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_minus_one.file_name().to_str().unwrap().to_owned(), position: draw::TOP_CAROUSEL_TXT_COORDS, undraw: true, is_selected: false,}).unwrap();
-    }
-    if let Some(current_idx) = current_idx {
-        let kind = determine_icon_to_draw(current_idx);
-        let mtime = current_idx.metadata().unwrap().mtime();
-        let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-        let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-        // file size, 
-        draw_tx.send(DrawCommand::Text { content: utils::format_bytes(current_idx.metadata().unwrap().size()), position: ENTRY_META_FILESIZE_TEXT_COORDS, undraw: true, is_selected: true }).unwrap();
-        // last modified
-        draw_tx.send(DrawCommand::Text { content: last_modified, position: draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, undraw: true, is_selected: true }).unwrap();
-        // icon
-        draw_tx.send(DrawCommand::DrawIcon { point: MIDDLE_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        // file name
-        draw_tx.send(DrawCommand::Text { content: current_idx.file_name().to_str().unwrap().to_owned(), position: draw::MIDDLE_CAROUSEL_TXT_COORDS, undraw: true, is_selected: true,}).unwrap();
+// "3/12", or "0/0" for an empty dir
+fn format_file_index(dir: &dir_model::DirModel, index: usize) -> String {
+    if dir.len() == 0 {
+        format!("{}/{}", 0, dir.len())
     }
-    if let Some(idx_plus_one) = idx_plus_one {
-        let kind = determine_icon_to_draw(idx_plus_one);
-        draw_tx.send(DrawCommand::DrawIcon { point: BOTTOM_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_plus_one.file_name().to_str().unwrap().to_owned(), position: draw::BOTTOM_CAROUSEL_TXT_COORDS, undraw: true, is_selected: false,}).unwrap();
-    }
-
-    // draw indexes based on new upcoming states
-    if let Some(current_idx) = current_idx {
-        let kind = determine_icon_to_draw(current_idx);
-        draw_tx.send(DrawCommand::DrawIcon { point: BOTTOM_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: current_idx.file_name().to_str().unwrap().to_owned(), position: draw::BOTTOM_CAROUSEL_TXT_COORDS, undraw: false, is_selected: true,}).unwrap();
+    else {
+        format!("{}/{}", index + 1, dir.len())
     }
-    if let Some(idx_minus_one) = idx_minus_one {
-        let kind = determine_icon_to_draw(idx_minus_one);
-        let mtime = idx_minus_one.metadata().unwrap().mtime();
-        let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-        let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-        // file size, 
-        draw_tx.send(DrawCommand::Text { content: utils::format_bytes(idx_minus_one.metadata().unwrap().size()), position: ENTRY_META_FILESIZE_TEXT_COORDS, undraw: false, is_selected: true }).unwrap();
-        // last modified
-        draw_tx.send(DrawCommand::Text { content: last_modified, position: draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, undraw: false, is_selected: true }).unwrap();
-        draw_tx.send(DrawCommand::DrawIcon { point: MIDDLE_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_minus_one.file_name().to_str().unwrap().to_owned(), position: draw::MIDDLE_CAROUSEL_TXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+}
+// the panel works out what changed and only redraws that, sliding the carousel over
+fn scroll_up(nav_state: &mut NavigatingData, draw_tx: &mpsc::Sender<DrawCommand>) {
+    if let Some(new_index) = nav_state.current_index.checked_sub(1) {
+        jump_to(nav_state, new_index, draw_tx);
     }
-    if let Some(idx_minus_two) = idx_minus_two {
-        let kind = determine_icon_to_draw(idx_minus_two);
-        draw_tx.send(DrawCommand::DrawIcon { point: TOP_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_minus_two.file_name().to_str().unwrap().to_owned(), position: draw::TOP_CAROUSEL_TXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+}
+fn scroll_down(nav_state: &mut NavigatingData, draw_tx: &mpsc::Sender<DrawCommand>) {
+    if nav_state.current_index + 1 < nav_state.dir.len() {
+        jump_to(nav_state, nav_state.current_index + 1, draw_tx);
     }
-
-    // undraw and draw the new current index
-    // draw_tx.send(DrawCommand::Text { content: format!("1/{}", file_count), position: Point::new(46, 18), undraw: false, is_selected: false }).unwrap();
-    draw_tx.send(DrawCommand::Text { content: format!("{}/{}", nav_state.current_index + 1, nav_state.file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: true, is_selected: false,}).unwrap();
-    draw_tx.send(DrawCommand::Text { content: format!("{}/{}", nav_state.current_index, nav_state.file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: false, is_selected: false,}).unwrap();
 }
-fn scroll_down(nav_state: &NavigatingData, draw_tx: mpsc::Sender<DrawCommand>) {
-    let readdir: Vec<_> = std::fs::read_dir(nav_state.current_dir.to_owned()).unwrap().collect::<Result<_, _>>().unwrap();
-    let idx_minus_one = { if nav_state.current_index == 0 { None } else { readdir.get(nav_state.current_index - 1) } };
-    let current_idx = readdir.get(nav_state.current_index);
-    let idx_plus_one = readdir.get(nav_state.current_index + 1);
-    let idx_plus_two = readdir.get(nav_state.current_index + 2);
-
-    // undraw based on indexes available
-    if let Some(idx_plus_one) = idx_plus_one {
-        let kind = determine_icon_to_draw(idx_plus_one);
-        draw_tx.send(DrawCommand::DrawIcon { point: BOTTOM_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_plus_one.file_name().to_str().unwrap().to_owned(), position: draw::BOTTOM_CAROUSEL_TXT_COORDS, undraw: true, is_selected: false,}).unwrap();
+// moves the selection anywhere in the current dir, for scrolling, paging and search
+fn jump_to(nav_state: &mut NavigatingData, new_index: usize, draw_tx: &mpsc::Sender<DrawCommand>) {
+    if new_index == nav_state.current_index || new_index >= nav_state.dir.len() {
+        return;
     }
-    if let Some(current_idx) = current_idx {
-        let kind = determine_icon_to_draw(current_idx);
-        let mtime = current_idx.metadata().unwrap().mtime();
-        let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-        let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-        // file size, 
-        draw_tx.send(DrawCommand::Text { content: utils::format_bytes(current_idx.metadata().unwrap().size()), position: ENTRY_META_FILESIZE_TEXT_COORDS, undraw: true, is_selected: true }).unwrap();
-        // last modified
-        draw_tx.send(DrawCommand::Text { content: last_modified, position: draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, undraw: true, is_selected: true }).unwrap();
-        draw_tx.send(DrawCommand::DrawIcon { point: MIDDLE_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: current_idx.file_name().to_str().unwrap().to_owned(), position: draw::MIDDLE_CAROUSEL_TXT_COORDS, undraw: true, is_selected: true,}).unwrap();
+    // only next to each other slides, a page or search jump just swaps
+    let transition = match new_index as i64 - nav_state.current_index as i64 {
+        1 => nav_state.transition(nav_panel::Transition::Down),
+        -1 => nav_state.transition(nav_panel::Transition::Up),
+        _ => nav_panel::Transition::None,
+    };
+    nav_state.current_index = new_index;
+    change_dir(&nav_state.dir, new_index, transition, draw_tx);
+}
+// shows new_dir w/ new_index selected, or the same dir w/ a new selection
+fn change_dir(new_dir: &dir_model::DirModel, new_index: usize, transition: nav_panel::Transition, draw_tx: &mpsc::Sender<DrawCommand>) {
+    draw_tx.send(DrawCommand::NavigatingUpdate { view: Box::new(nav_panel::NavView::new(new_dir, new_index)), transition }).unwrap();
+}
+// where the cursor goes in a dir we're about to show: on the folder we just came up out of,
+// otherwise on whatever was selected last time we were in it, otherwise the top
+fn restored_index(nav_state: &NavigatingData, new_dir: &dir_model::DirModel) -> usize {
+    new_dir.position_of(&nav_state.dir.path)
+        .or_else(|| nav_state.cursor_history.get(&new_dir.path).and_then(|selected| new_dir.position_of(selected)))
+        .unwrap_or(0)
+}
+// real dirs come off the disk, virtual ones out of the lists. the media root gets the virtual folders on top
+fn load_dir(nav_state: &NavigatingData, path: &Path) -> std::io::Result<dir_model::DirModel> {
+    let (sort_key, filter) = (nav_state.dir.sort_key, nav_state.dir.filter);
+    if let Some(virtual_dir) = lists::virtual_dir_at(&nav_state.root, path) {
+        let library = nav_state.indexer.library.lock().unwrap();
+        return Ok(nav_state.lists.dir_model(virtual_dir, &library, &nav_state.root, sort_key, filter));
     }
-    if let Some(idx_minus_one) = idx_minus_one {
-        let kind = determine_icon_to_draw(idx_minus_one);
-        draw_tx.send(DrawCommand::DrawIcon { point: TOP_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_minus_one.file_name().to_str().unwrap().to_owned(), position: draw::TOP_CAROUSEL_TXT_COORDS, undraw: true, is_selected: false,}).unwrap();
+    // the index has every folder it's walked, anything else (ex. the usb stick) is read off the disk
+    let listed = nav_state.indexer.library.lock().unwrap().list_dir(path);
+    let mut dir = match listed {
+        Some(entries) => dir_model::DirModel::from_entries(path, entries, sort_key, filter),
+        None => dir_model::DirModel::load(path, sort_key, filter)?,
+    };
+    if path == nav_state.root {
+        dir.entries.splice(0..0, lists::virtual_entries(&nav_state.root));
+        // the real mount point, not just any folder that happens to be called USB
+        let usb_mount_point = usb::mount_point(&nav_state.root);
+        for entry in dir.entries.iter_mut().filter(|entry| entry.is_dir && entry.path == usb_mount_point) {
+            entry.kind = IconKind::Usb;
+        }
     }
-
-    // draw indexes based on new upcoming states
-    if let Some(current_idx) = current_idx {
-        let kind = determine_icon_to_draw(current_idx);
-        draw_tx.send(DrawCommand::DrawIcon { point: TOP_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: current_idx.file_name().to_str().unwrap().to_owned(), position: draw::TOP_CAROUSEL_TXT_COORDS, undraw: false, is_selected: true,}).unwrap();
+    Ok(dir)
+}
+// back to the top of the media root, for when the dir we're in disappears (ex. usb stick pulled out)
+fn leave_to_root(nav_state: &mut NavigatingData, redraw: bool, draw_tx: &mpsc::Sender<DrawCommand>) {
+    let new_dir = load_dir(nav_state, &nav_state.root)
+        .unwrap_or_else(|_| dir_model::DirModel::empty(&nav_state.root, nav_state.dir.sort_key, nav_state.dir.filter));
+    let new_index = restored_index(nav_state, &new_dir);
+    if redraw {
+        change_dir(&new_dir, new_index, nav_panel::Transition::None, draw_tx);
     }
-    if let Some(idx_plus_one) = idx_plus_one {
-        let kind = determine_icon_to_draw(idx_plus_one);
-        let mtime = idx_plus_one.metadata().unwrap().mtime();
-        let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-        let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-        // file size, 
-        draw_tx.send(DrawCommand::Text { content: utils::format_bytes(idx_plus_one.metadata().unwrap().size()), position: ENTRY_META_FILESIZE_TEXT_COORDS, undraw: false, is_selected: true }).unwrap();
-        // last modified
-        draw_tx.send(DrawCommand::Text { content: last_modified, position: draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, undraw: false, is_selected: true }).unwrap();
-        draw_tx.send(DrawCommand::DrawIcon { point: MIDDLE_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_plus_one.file_name().to_str().unwrap().to_owned(), position: draw::MIDDLE_CAROUSEL_TXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+    switch_dir(nav_state, new_dir, new_index);
+}
+// remembers what was selected in the dir we're leaving, then moves into the new one
+fn switch_dir(nav_state: &mut NavigatingData, new_dir: dir_model::DirModel, new_index: usize) {
+    if let Some(selected) = selected_entry_path(nav_state) {
+        nav_state.cursor_history.insert(nav_state.dir.path.clone(), selected);
     }
-    if let Some(idx_plus_two) = idx_plus_two {
-        let kind = determine_icon_to_draw(idx_plus_two);
-        draw_tx.send(DrawCommand::DrawIcon { point: BOTTOM_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_plus_two.file_name().to_str().unwrap().to_owned(), position: draw::BOTTOM_CAROUSEL_TXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+    nav_state.dir = new_dir;
+    nav_state.current_index = new_index;
+}
+// reloads the listing after the watcher saw a change, keeping the same entry selected if it's still there
+fn refresh_dir(nav_state: &mut NavigatingData, redraw: bool, draw_tx: &mpsc::Sender<DrawCommand>) {
+    let new_dir = match load_dir(nav_state, &nav_state.dir.path) {
+        Ok(new_dir) => new_dir,
+        Err(e) => {
+            println!("could not reload {}: {:#?}", nav_state.dir.path.display(), e);
+            return;
+        }
+    };
+    let new_index = selected_entry_path(nav_state)
+        .and_then(|path| new_dir.position_of(&path))
+        .unwrap_or(nav_state.current_index.min(new_dir.len().saturating_sub(1)));
+
+    if redraw {
+        change_dir(&new_dir, new_index, nav_panel::Transition::None, draw_tx);
     }
-    draw_tx.send(DrawCommand::Text { content: format!("{}/{}", nav_state.current_index + 1, nav_state.file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: true, is_selected: false,}).unwrap();
-    draw_tx.send(DrawCommand::Text { content: format!("{}/{}", nav_state.current_index + 2, nav_state.file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: false, is_selected: false,}).unwrap();
+    nav_state.dir = new_dir;
+    nav_state.current_index = new_index;
+}
+// path of whatever's in the middle of the carousel
+fn selected_entry_path(nav_state: &NavigatingData) -> Option<PathBuf> {
+    nav_state.dir.get(nav_state.current_index).map(|entry| entry.path.clone())
 }
 enum SelectResponse {
     // (file type, file size, file name)
@@ -1525,111 +2201,34 @@ enum SelectResponse {
         file_path: PathBuf,
         last_modified: String,
     },
-    Directory{
-        file_path: PathBuf,
-        file_count: usize,
-        last_modified: String,
-    },
+    // and where the cursor should go in it
+    Directory(dir_model::DirModel, usize),
     Error(String),
     FatalError(String),
 }
 
 fn enter_dir_or_select_file(nav_state: &NavigatingData, draw_tx: mpsc::Sender<DrawCommand>) -> SelectResponse {
-    let readdir: Vec<_> = std::fs::read_dir(nav_state.current_dir.to_owned()).unwrap().collect::<Result<_, _>>().unwrap();
-    let idx_plus_one = readdir.get(nav_state.current_index + 1);
-    let current_idx = readdir.get(nav_state.current_index);
-    let idx_minus_one = { if nav_state.current_index == 0 { None } else { readdir.get(nav_state.current_index - 1) } };
-
-    if let Some(entry) = readdir.get(nav_state.current_index) {
-        if let Ok(meta) = entry.metadata() {
-            if meta.is_dir() {
-                let new_dir: Vec<_> = std::fs::read_dir(entry.path()).unwrap().collect::<Result<_, _>>().unwrap();
-                // undraw the current current_index/file_count,
-                draw_tx.send(DrawCommand::Text { content: format!("{}/{}", nav_state.current_index + 1, nav_state.file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: true, is_selected: false,}).unwrap();
-                draw_tx.send(DrawCommand::Text { content: format_dir(nav_state.current_dir.to_owned()), position: draw::TOP_NAV_PATH_COORDS, undraw: true, is_selected: false,}).unwrap();
-
-                // undraw possible idx_plus_one, current_idx, and idx_minus_one
-                if let Some(idx_plus_one) = idx_plus_one {
-                    let kind = determine_icon_to_draw(idx_plus_one);
-                    draw_tx.send(DrawCommand::DrawIcon { point: BOTTOM_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-                    draw_tx.send(DrawCommand::Text { content: idx_plus_one.file_name().to_str().unwrap().to_owned(), position: draw::BOTTOM_CAROUSEL_TXT_COORDS, undraw: true, is_selected: false,}).unwrap();
-                }
-                if let Some(current_idx) = current_idx {
-                    let kind = determine_icon_to_draw(current_idx);
-                    let mtime = current_idx.metadata().unwrap().mtime();
-                    let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-                    let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-                    // file size, 
-                    draw_tx.send(DrawCommand::Text { content: utils::format_bytes(current_idx.metadata().unwrap().size()), position: ENTRY_META_FILESIZE_TEXT_COORDS, undraw: true, is_selected: true }).unwrap();
-                    // last modified
-                    draw_tx.send(DrawCommand::Text { content: last_modified, position: draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, undraw: true, is_selected: true }).unwrap();
-                    draw_tx.send(DrawCommand::DrawIcon { point: MIDDLE_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-                    draw_tx.send(DrawCommand::Text { content: current_idx.file_name().to_str().unwrap().to_owned(), position: draw::MIDDLE_CAROUSEL_TXT_COORDS, undraw: true, is_selected: true,}).unwrap();
-                }
-                if let Some(idx_minus_one) = idx_minus_one {
-                    let kind = determine_icon_to_draw(idx_minus_one);
-                    draw_tx.send(DrawCommand::DrawIcon { point: TOP_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-                    draw_tx.send(DrawCommand::Text { content: idx_minus_one.file_name().to_str().unwrap().to_owned(), position: draw::TOP_CAROUSEL_TXT_COORDS, undraw: true, is_selected: false,}).unwrap();
-                }
-
-                // new path is the one u selected, draw their stuff if exists
-                let file_count = new_dir.iter().count();
-                let new_current_idx = new_dir.get(0);
-                let new_idx_plus_one = new_dir.get(1);
-
-                if file_count == 0 {
-                    draw_tx.send(DrawCommand::Text { content: format!("{}/{}", 0, file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: false, is_selected: false,}).unwrap();
-                }
-                else {
-                    draw_tx.send(DrawCommand::Text { content: format!("{}/{}", 1, file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: false, is_selected: false,}).unwrap();
+    if let Some(entry) = nav_state.dir.get(nav_state.current_index) {
+        if entry.is_dir {
+            match load_dir(nav_state, &entry.path) {
+                Ok(new_dir) => {
+                    let new_index = restored_index(nav_state, &new_dir);
+                    change_dir(&new_dir, new_index, nav_state.transition(nav_panel::Transition::Enter), &draw_tx);
+                    SelectResponse::Directory(new_dir, new_index)
                 }
-                if let Some(new_idx_plus_one) = new_idx_plus_one {
-                    let kind = determine_icon_to_draw(new_idx_plus_one);
-                    draw_tx.send(DrawCommand::DrawIcon { point: BOTTOM_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-                    draw_tx.send(DrawCommand::Text { content: new_idx_plus_one.file_name().to_str().unwrap().to_owned(), position: draw::BOTTOM_CAROUSEL_TXT_COORDS, undraw: false, is_selected: false,}).unwrap();
-                }
-                if let Some(new_current_idx) = new_current_idx {
-                    let kind = determine_icon_to_draw(new_current_idx);
-                    let mtime = new_current_idx.metadata().unwrap().mtime();
-                    let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-                    let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-                    // file size, 
-                    draw_tx.send(DrawCommand::Text { content: utils::format_bytes(new_current_idx.metadata().unwrap().size()), position: ENTRY_META_FILESIZE_TEXT_COORDS, undraw: false, is_selected: true }).unwrap();
-                    // last modified
-                    draw_tx.send(DrawCommand::Text { content: last_modified, position: draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, undraw: false, is_selected: true }).unwrap();
-                    draw_tx.send(DrawCommand::DrawIcon { point: MIDDLE_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-                    draw_tx.send(DrawCommand::Text { content: new_current_idx.file_name().to_str().unwrap().to_owned(), position: draw::MIDDLE_CAROUSEL_TXT_COORDS, undraw: false, is_selected: true,}).unwrap();
-                }
-                draw_tx.send(DrawCommand::Text { content: format_dir(entry.path().to_owned()), position: draw::TOP_NAV_PATH_COORDS, undraw: false, is_selected: false,}).unwrap();
-                let mtime = entry.metadata().unwrap().mtime();
-                let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-                let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-                SelectResponse::Directory { file_path: entry.path(), file_count: file_count, last_modified } 
-            }
-            else if meta.is_file() {
-                // check extension
-                // if .raw or .rgb565, prompt to play
-
-                // for now, just play
-                println!("This is a file!");
-                let path = entry.path();
-                let file_extension = path.extension();
-                if let Some(file_extension) = file_extension {
-                    let mtime = entry.metadata().unwrap().mtime();
-                    let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-                    let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-                    SelectResponse::File { file_name: entry.file_name().to_str().unwrap().to_owned(), file_size: meta.len(), file_extension: file_extension.to_str().unwrap().to_owned(), file_path: path, last_modified }
-                }
-                else {
-                    SelectResponse::Error(String::from("Filetype error: File can not be opened, must be either a .mp3, rgb565, or .raw file."))
-                }
-            }
-            else {
-                SelectResponse::Error(String::from("Filetype error: File can not be opened: must be either a .mp3, rgb565, or .raw file."))
+                Err(e) => SelectResponse::Error(format!("Could not open {}: {}", entry.name, e)),
             }
         }
+        else if entry.path.is_file() {
+            // check extension
+            // if .raw or .rgb565, prompt to play
+            println!("This is a file!");
+            // dotfiles like .bashrc have no extension, they still go to the reader/hex inspector
+            let file_extension = entry.path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+            SelectResponse::File { file_name: entry.name.clone(), file_size: entry.size, file_extension, file_path: entry.path.clone(), last_modified: entry.last_modified() }
+        }
         else {
-                SelectResponse::Error(String::from("Unknown error: File could not be opened."))
+            SelectResponse::Error(String::from("Unknown error: File could not be opened."))
         }
     }
     else {
@@ -1637,81 +2236,82 @@ fn enter_dir_or_select_file(nav_state: &NavigatingData, draw_tx: mpsc::Sender<Dr
     }
 }
 
-fn exit_dir(nav_state: &NavigatingData, draw_tx: mpsc::Sender<DrawCommand>) -> Option<(PathBuf, usize)> {
-    let readdir: Vec<_> = std::fs::read_dir(nav_state.current_dir.to_owned()).unwrap().collect::<Result<_, _>>().unwrap();
-    let idx_plus_one = readdir.get(nav_state.current_index + 1);
-    let current_idx = readdir.get(nav_state.current_index);
-    let idx_minus_one = { if nav_state.current_index == 0 { None } else { readdir.get(nav_state.current_index - 1) } };
-
-    // undraw the current current_index/file_count,
-    if nav_state.file_count == 0 {
-        draw_tx.send(DrawCommand::Text { content: format!("{}/{}", 0, nav_state.file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: true, is_selected: false,}).unwrap();
-    }
-    else {
-        draw_tx.send(DrawCommand::Text { content: format!("{}/{}", nav_state.current_index + 1, nav_state.file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: true, is_selected: false,}).unwrap();
-    }
-    draw_tx.send(DrawCommand::Text { content: format_dir(nav_state.current_dir.to_owned()), position: draw::TOP_NAV_PATH_COORDS, undraw: true, is_selected: false,}).unwrap();
-    // if there are files or dirs, undraw them
-    if let Some(idx_plus_one) = idx_plus_one {
-        let kind = determine_icon_to_draw(idx_plus_one);
-        draw_tx.send(DrawCommand::DrawIcon { point: BOTTOM_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_plus_one.file_name().to_str().unwrap().to_owned(), position: draw::BOTTOM_CAROUSEL_TXT_COORDS, undraw: true, is_selected: false,}).unwrap();
-    }
-    if let Some(current_idx) = current_idx {
-        let kind = determine_icon_to_draw(current_idx);
-        let mtime = current_idx.metadata().unwrap().mtime();
-        let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-        let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-        // file size, 
-        draw_tx.send(DrawCommand::Text { content: utils::format_bytes(current_idx.metadata().unwrap().size()), position: ENTRY_META_FILESIZE_TEXT_COORDS, undraw: true, is_selected: true }).unwrap();
-        // last modified
-        draw_tx.send(DrawCommand::Text { content: last_modified, position: draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, undraw: true, is_selected: true }).unwrap();
-        draw_tx.send(DrawCommand::DrawIcon { point: MIDDLE_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: current_idx.file_name().to_str().unwrap().to_owned(), position: draw::MIDDLE_CAROUSEL_TXT_COORDS, undraw: true, is_selected: true,}).unwrap();
-    }
-    if let Some(idx_minus_one) = idx_minus_one {
-        let kind = determine_icon_to_draw(idx_minus_one);
-        draw_tx.send(DrawCommand::DrawIcon { point: TOP_CAROUSEL_ICON_COORDS, kind, undraw: true }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: idx_minus_one.file_name().to_str().unwrap().to_owned(), position: draw::TOP_CAROUSEL_TXT_COORDS, undraw: true, is_selected: false,}).unwrap();
-    }
-
-    // go up one in current directory
-    let mut new_path = nav_state.current_dir.to_owned();
-    new_path.pop();
-    let new_dir: Vec<_> = std::fs::read_dir(new_path.to_owned()).unwrap().collect::<Result<_, _>>().unwrap();
-    println!("new directory: {:#?}", new_dir);
-    let file_count = new_dir.iter().count();
-    let new_current_idx = new_dir.get(0);
-    let new_idx_plus_one = new_dir.get(1);
-
-    if file_count == 0 {
-        draw_tx.send(DrawCommand::Text { content: format!("{}/{}", 0, file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: false, is_selected: false,}).unwrap();
+enum ExitResponse {
+    // and where the cursor should go in it
+    Directory(dir_model::DirModel, usize),
+    // already at the media root, nothing above it to go to
+    AtRoot,
+    Error(String),
+}
+fn exit_dir(nav_state: &NavigatingData, draw_tx: mpsc::Sender<DrawCommand>) -> ExitResponse {
+    // go up one in current directory, but never past the media root
+    if nav_state.dir.path == nav_state.root || !nav_state.dir.path.starts_with(&nav_state.root) {
+        return ExitResponse::AtRoot;
     }
-    else {
-        draw_tx.send(DrawCommand::Text { content: format!("{}/{}", 1, file_count), position: draw::TOP_NAV_FILE_INDEX_COORDS, undraw: false, is_selected: false,}).unwrap();
+    let Some(new_path) = nav_state.dir.path.parent() else {
+        return ExitResponse::AtRoot;
+    };
+    match load_dir(nav_state, new_path) {
+        Ok(new_dir) => {
+            println!("new directory: {:?}", new_dir.path);
+            let new_index = restored_index(nav_state, &new_dir);
+            change_dir(&new_dir, new_index, nav_state.transition(nav_panel::Transition::Leave), &draw_tx);
+            ExitResponse::Directory(new_dir, new_index)
+        }
+        Err(e) => ExitResponse::Error(format!("Could not open {}: {}", new_path.display(), e)),
     }
-    if let Some(new_idx_plus_one) = new_idx_plus_one {
-        let kind = determine_icon_to_draw(new_idx_plus_one);
-        draw_tx.send(DrawCommand::DrawIcon { point: BOTTOM_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: new_idx_plus_one.file_name().to_str().unwrap().to_owned(), position: draw::BOTTOM_CAROUSEL_TXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+}
+// puts a setting that was just changed into effect and saves the config
+fn apply_setting(state: &mut State, setting: settings::Setting, draw_tx: &mpsc::Sender<DrawCommand>) {
+    match setting {
+        settings::Setting::Brightness => {
+            draw_tx.send(DrawCommand::Brightness { percent: state.config.brightness }).unwrap();
+        }
+        settings::Setting::SortKey | settings::Setting::Filter => {
+            // the navigator gets redrawn w/ this when the settings are closed
+            state.nav_state.dir.sort_key = state.config.sort_key;
+            state.nav_state.dir.filter = state.config.filter;
+            refresh_dir(&mut state.nav_state, false, draw_tx);
+        }
+        settings::Setting::Clock => redraw_clock(state, draw_tx),
+        settings::Setting::WeatherLocation => {
+            // the weather thread might've died, that's no reason to take everything down
+            let _ = state.weather_location_tx.send(state.config.weather_location.clone());
+        }
+        settings::Setting::Theme => {
+            state.theme = theme::Theme::load(&state.config.theme);
+            draw_tx.send(DrawCommand::Theme { theme: state.theme.clone() }).unwrap();
+        }
+        settings::Setting::Animations => state.nav_state.animations = state.config.animations,
+        // read when they're needed
+        settings::Setting::SleepAfter | settings::Setting::VolumeStep => (),
     }
-    if let Some(new_current_idx) = new_current_idx {
-        let kind = determine_icon_to_draw(new_current_idx);
-        let mtime = new_current_idx.metadata().unwrap().mtime();
-        let unformatted_last_modified = DateTime::from_timestamp(mtime, 0).unwrap().naive_local();
-        let last_modified = unformatted_last_modified.format("%m-%d-%Y, %-I:%M%P").to_string();
-        // file size, 
-        draw_tx.send(DrawCommand::Text { content: utils::format_bytes(new_current_idx.metadata().unwrap().size()), position: ENTRY_META_FILESIZE_TEXT_COORDS, undraw: false, is_selected: true }).unwrap();
-        // last modified
-        draw_tx.send(DrawCommand::Text { content: last_modified, position: draw::ENTRY_META_LASTMODIFIED_TEXT_COORDS, undraw: false, is_selected: true }).unwrap();
-        draw_tx.send(DrawCommand::DrawIcon { point: MIDDLE_CAROUSEL_ICON_COORDS, kind, undraw: false }).unwrap();
-        draw_tx.send(DrawCommand::Text { content: new_current_idx.file_name().to_str().unwrap().to_owned(), position: draw::MIDDLE_CAROUSEL_TXT_COORDS, undraw: false, is_selected: true,}).unwrap();
+    state.config.save();
+}
+// back from a full screen view/modal, label is whatever the 1st small screen shows right now
+fn return_to_navigator(state: &mut State, draw_tx: &mpsc::Sender<DrawCommand>, i2c_label: &str) {
+    {
+        let current_state = state.current_state.clone();
+        let mut current_state = current_state.lock().unwrap();
+        *current_state = DisplayState::Navigating;
     }
-    draw_tx.send(DrawCommand::Text { content: format_dir(new_path.to_owned()), position: draw::TOP_NAV_PATH_COORDS, undraw: false, is_selected: false,}).unwrap();
-    Some((new_path, file_count))
+    draw_tx.send(DrawCommand::DrawI2CText { content: i2c_label.to_string(), position: Point::zero(), undraw: true, screen: false }).unwrap();
+    draw_tx.send(DrawCommand::DrawI2CText { content: "Navigating".to_string(), position: Point::zero(), undraw: false, screen: false }).unwrap();
+    draw_tx.send(DrawCommand::NavigatingBackground { dir: state.nav_state.dir.clone(), current_index: state.nav_state.current_index }).unwrap();
 }
 
-fn play_video(current_frame: Arc<AtomicU64>, paused: Arc<AtomicBool>, file_details: Option<FileDetails>, draw_tx: mpsc::Sender<DrawCommand>) {
+// plays from current_frame in a new thread. pausing and resuming quicker than a frame would otherwise leave the old one running too
+fn start_playback(video_state: &PlayingSomethingData, file_details: Option<FileDetails>, draw_tx: &mpsc::Sender<DrawCommand>) {
+    let generation = video_state.playback.fetch_add(1, Ordering::AcqRel) + 1;
+    let draw_tx1 = draw_tx.clone();
+    let paused1 = video_state.paused.clone();
+    let playback1 = video_state.playback.clone();
+    let current_frame1 = video_state.current_frame.clone();
+    thread::spawn(move || {
+        play_video(current_frame1, paused1, playback1, generation, file_details, draw_tx1);
+    });
+}
+fn play_video(current_frame: Arc<AtomicU64>, paused: Arc<AtomicBool>, playback: Arc<AtomicU64>, generation: u64, file_details: Option<FileDetails>, draw_tx: mpsc::Sender<DrawCommand>) {
     if let Some(file_details) = file_details {
         // 2 bytes per pixel btw
         let frame_bytes = WIDTH as usize * HEIGHT as usize * 2;
@@ -1730,7 +2330,7 @@ fn play_video(current_frame: Arc<AtomicU64>, paused: Arc<AtomicBool>, file_detai
         // start from current frame
         while let Ok(()) = video_file.read_exact(&mut frame) {
             {
-                if paused.load(Ordering::Acquire) == true {
+                if paused.load(Ordering::Acquire) || playback.load(Ordering::Acquire) != generation {
                     break;
                 }
                 else {
@@ -1742,35 +2342,39 @@ fn play_video(current_frame: Arc<AtomicU64>, paused: Arc<AtomicBool>, file_detai
         }
     }
 }
-fn current_weather_task(current_state: Arc<Mutex<DisplayState>>, current_weather: Arc<Mutex<String>>, draw_tx: mpsc::Sender<DrawCommand>) {
+// location_rx gets a new location from the settings, it's fetched for right away instead of at the next hourly check.
+// always kept up to date, the navigator shows whatever came in last once it's back
+fn current_weather_task(current_weather: Arc<Mutex<String>>, mut location: String, location_rx: mpsc::Receiver<String>, draw_tx: mpsc::Sender<DrawCommand>) {
     let weather_api_key = std::env::var("WEATHER_API_KEY");
     match weather_api_key {
         Ok(key) => {
+            // only toasted when it's for a location that was just changed, not every hour
+            let mut location_changed = false;
             loop {
-                match &weather("Edmonton,AB", "celcius", "en", &key) {
+                match &weather(&location, "celcius", "en", &key) {
                     Ok(current) => {
-                        let current_state = current_state.lock().unwrap();
-                        match *current_state {
-                            DisplayState::Navigating => {
-                                println!("Got the weather, it is {}", current.weather[0].main.to_string());
-                                let mut current_weather = current_weather.lock().unwrap();
-                                let new_weather_as_string = current.weather[0].main.to_string();
-                                // redraw current weather
-                                draw_tx.send(DrawCommand::Text { content: current_weather.clone(), position: draw::TOP_NAV_WEATHER_TEXT_COORDS, undraw: true, is_selected: false,}).unwrap();
-                                draw_tx.send(DrawCommand::Text { content: new_weather_as_string.clone(), position: draw::TOP_NAV_WEATHER_TEXT_COORDS, undraw: false, is_selected: false,}).unwrap();
-                                *current_weather = new_weather_as_string;
-                            }
-                            _ => (),
+                        println!("Got the weather, it is {}", current.weather[0].main.to_string());
+                        let mut current_weather = current_weather.lock().unwrap();
+                        let new_weather_as_string = current.weather[0].main.to_string();
+                        // redraw current weather
+                        draw_tx.send(DrawCommand::Weather { weather: new_weather_as_string.clone() }).unwrap();
+                        *current_weather = new_weather_as_string;
+                        if location_changed {
+                            show_toast(&draw_tx, "Weather updated", toast::ToastKind::Info);
                         }
                     }
                     Err(e) => {
                         println!("Problem getting weather: {:#?}", e);
                         let mut current_weather = current_weather.lock().unwrap();
                         *current_weather = "?".to_string();
-                        draw_tx.send(DrawCommand::Text { content: "?".to_string(), position: draw::TOP_NAV_WEATHER_TEXT_COORDS, undraw: false, is_selected: false,}).unwrap();
+                        draw_tx.send(DrawCommand::Weather { weather: "?".to_string() }).unwrap();
                     }
                 }
-                thread::sleep(Duration::from_mins(61));
+                location_changed = false;
+                if let Ok(new_location) = location_rx.recv_timeout(Duration::from_mins(61)) {
+                    location = new_location;
+                    location_changed = true;
+                }
             }
         }
         Err(err) => {
@@ -0,0 +1,176 @@
+use embedded_graphics::prelude::*;
+
+use crate::{fonts, theme::{Role, Theme}, widgets::{Node, Screen, WidgetKind, LIST_ROW_HEIGHT}, HEIGHT, WIDTH};
+
+// ids for options side by side along the bottom, any more than this and they get listed instead
+const OPTION_IDS: [&str; 4] = ["option_0", "option_1", "option_2", "option_3"];
+const BOX_TOP_LEFT: Point = Point::new(40, 40);
+const BOX_SIZE: Size = Size::new(240, 160);
+// between the border and everything in it
+const PADDING: u32 = 16;
+const BUTTON_HEIGHT: u32 = 20;
+const BUTTON_SPACING: u32 = 10;
+// listed options showing at once, the list scrolls w/ the selection past that
+const MAX_LISTED: usize = 4;
+
+// a message w/ any number of options, for confirmations, errors and small menus.
+// a long message gets scrolled through on the way down to the options
+#[derive(Clone, Debug)]
+pub struct Modal {
+    pub message: String,
+    pub options: Vec<String>,
+    pub selected: usize,
+    // first message line showing
+    scroll: usize,
+}
+
+// worked out from the theme's font each time, so the main loop and the draw task always agree
+struct Layout {
+    lines: Vec<String>,
+    // message lines that fit above the options
+    visible_lines: usize,
+    side_by_side: bool,
+    buttons_width: u32,
+    options_height: u32,
+}
+
+impl Modal {
+    pub fn new(message: &str, options: &[&str]) -> Modal {
+        Modal { message: message.to_string(), options: options.iter().map(|option| option.to_string()).collect(), selected: 0, scroll: 0 }
+    }
+    fn layout(&self, theme: &Theme) -> Layout {
+        let inner_width = BOX_SIZE.width - PADDING * 2;
+        let buttons_width = self.options.iter().map(|option| button_width(option, theme)).sum::<u32>() + BUTTON_SPACING * self.options.len().saturating_sub(1) as u32;
+        let side_by_side = self.options.len() <= OPTION_IDS.len() && buttons_width <= inner_width;
+        let options_height = if side_by_side { BUTTON_HEIGHT } else { self.options.len().min(MAX_LISTED) as u32 * LIST_ROW_HEIGHT };
+        let message_height = BOX_SIZE.height.saturating_sub(PADDING * 2 + PADDING / 2 + options_height);
+        Layout {
+            lines: fonts::wrap(&self.message, inner_width, theme.font),
+            visible_lines: (message_height / theme.font.character_size.height).max(1) as usize,
+            side_by_side,
+            buttons_width,
+            options_height,
+        }
+    }
+    // Up/Down walk one line: down scrolls the message to its end before moving through the options,
+    // up goes back through the options to the first one before scrolling the message back
+    pub fn move_selection(&mut self, down: bool, theme: &Theme) {
+        let layout = self.layout(theme);
+        let max_scroll = layout.lines.len().saturating_sub(layout.visible_lines);
+        if down {
+            if self.scroll < max_scroll {
+                self.scroll += 1;
+            }
+            else if self.selected + 1 < self.options.len() {
+                self.selected += 1;
+            }
+        }
+        else if self.selected > 0 {
+            self.selected -= 1;
+        }
+        else {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+    }
+    // laid out for this theme, shown w/ the current selection/scroll
+    pub fn screen(&self, theme: &Theme) -> Screen {
+        let layout = self.layout(theme);
+        let inner_width = BOX_SIZE.width - PADDING * 2;
+        let inner_left = BOX_TOP_LEFT.x + PADDING as i32;
+        let options_top = BOX_TOP_LEFT.y + (BOX_SIZE.height - PADDING - layout.options_height) as i32;
+        let options = if layout.side_by_side {
+            let buttons = self.options.iter()
+                .zip(OPTION_IDS)
+                .map(|(option, id)| Node::widget(id, WidgetKind::Button { text: option.clone(), selected: false }, button_width(option, theme), BUTTON_HEIGHT))
+                .collect();
+            (Point::new(WIDTH as i32 / 2 - layout.buttons_width as i32 / 2, options_top), Node::Row { spacing: BUTTON_SPACING, children: buttons })
+        }
+        else {
+            (Point::new(inner_left, options_top), Node::widget("options", WidgetKind::List { items: Vec::new(), selected: 0 }, inner_width, layout.options_height))
+        };
+        let message_height = layout.visible_lines as u32 * theme.font.character_size.height;
+        let mut screen = Screen::new(Node::Stack(vec![
+            (BOX_TOP_LEFT, Node::widget("border", WidgetKind::Frame { color: Role::Border, stroke_width: 2 }, BOX_SIZE.width, BOX_SIZE.height)),
+            (Point::new(inner_left, BOX_TOP_LEFT.y + PADDING as i32), Node::widget("message", WidgetKind::label(""), inner_width, message_height)),
+            options,
+        ]), WIDTH, HEIGHT);
+        self.update(&mut screen, theme);
+        screen
+    }
+    // brings a screen from screen() up to date, only what changed gets redrawn
+    pub fn update(&self, screen: &mut Screen, theme: &Theme) {
+        let layout = self.layout(theme);
+        let end = (self.scroll + layout.visible_lines).min(layout.lines.len());
+        screen.set("message", WidgetKind::label(&layout.lines[self.scroll.min(end)..end].join("\n")));
+        if layout.side_by_side {
+            for (idx, (option, id)) in self.options.iter().zip(OPTION_IDS).enumerate() {
+                screen.set(id, WidgetKind::Button { text: option.clone(), selected: idx == self.selected });
+            }
+        }
+        else {
+            // the window follows the selection
+            let first = (self.selected + 1).saturating_sub(MAX_LISTED);
+            let items = self.options.iter().skip(first).take(MAX_LISTED).cloned().collect();
+            screen.set("options", WidgetKind::List { items, selected: self.selected - first });
+        }
+    }
+}
+
+fn button_width(option: &str, theme: &Theme) -> u32 {
+    (fonts::text_width(option, theme.font) + 12).max(40)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (down, scroll after, selected after), one press after the other
+    fn check_moves(modal: &mut Modal, theme: &Theme, moves: &[(bool, usize, usize)]) {
+        for (press, &(down, scroll, selected)) in moves.iter().enumerate() {
+            modal.move_selection(down, theme);
+            assert_eq!((modal.scroll, modal.selected), (scroll, selected), "after press {}", press + 1);
+        }
+    }
+
+    #[test]
+    fn scrolls_message_before_options() {
+        let theme = Theme::default();
+        let layout_lines = |modal: &Modal| {
+            let layout = modal.layout(&theme);
+            (layout.lines.len(), layout.visible_lines)
+        };
+        // 3 lines more than fit
+        let mut modal = Modal::new("", &["No", "Yes"]);
+        let visible = layout_lines(&modal).1;
+        modal.message = (0..visible + 3).map(|line| format!("line {}", line)).collect::<Vec<_>>().join("\n");
+        assert_eq!(layout_lines(&modal), (visible + 3, visible));
+        check_moves(&mut modal, &theme, &[
+            (true, 1, 0),
+            (true, 2, 0),
+            (true, 3, 0),
+            // all the way down, now the options
+            (true, 3, 1),
+            // nowhere left to go
+            (true, 3, 1),
+            (false, 3, 0),
+            (false, 2, 0),
+            (false, 1, 0),
+            (false, 0, 0),
+            (false, 0, 0),
+        ]);
+    }
+
+    #[test]
+    fn short_message_goes_straight_to_options() {
+        let theme = Theme::default();
+        let mut modal = Modal::new("Delete it?", &["No", "Yes", "Maybe", "Later", "Never"]);
+        check_moves(&mut modal, &theme, &[
+            (true, 0, 1),
+            (true, 0, 2),
+            (true, 0, 3),
+            (true, 0, 4),
+            (true, 0, 4),
+            (false, 0, 3),
+        ]);
+    }
+}
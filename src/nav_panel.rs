@@ -0,0 +1,195 @@
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+use crate::{animation::Slide, dir_model::{DirModel, Entry}, fonts, format_dir, format_file_index, theme::{Role, Theme}, thumbnails, utils, widgets::{Glyph, Marquee, Node, Screen, WidgetKind}, HEIGHT, WIDTH};
+
+// (icon, name) per carousel slot, top to bottom. the outer two are just off the edges so they can slide in
+const CAROUSEL_IDS: [(&str, &str); 5] = [
+    ("far_top_icon", "far_top_name"),
+    ("top_icon", "top_name"),
+    ("middle_icon", "middle_name"),
+    ("bottom_icon", "bottom_name"),
+    ("far_bottom_icon", "far_bottom_name"),
+];
+const SELECTED: usize = 2;
+const CAROUSEL_NAME_WIDTH: u32 = 240;
+const CAROUSEL_ROW_HEIGHT: u32 = 40;
+const CAROUSEL_SPACING: u32 = 10;
+const CAROUSEL_PITCH: i32 = (CAROUSEL_ROW_HEIGHT + CAROUSEL_SPACING) as i32;
+
+// how the carousel gets from what it showed to the next view
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Transition {
+    None,
+    // selection moved to the entry above/below
+    Up,
+    Down,
+    // into a dir, slides in from the right
+    Enter,
+    // back out to the parent, slides in from the left
+    Leave,
+}
+
+// everything the navigator shows about one dir/selection, built by the main loop
+#[derive(Clone, Debug)]
+pub struct NavView {
+    pub path: String,
+    pub file_index: String,
+    // two above, selected, two below
+    pub carousel: [Option<Entry>; 5],
+}
+impl NavView {
+    pub fn new(dir: &DirModel, index: usize) -> NavView {
+        NavView {
+            path: format_dir(dir.path.to_owned()),
+            file_index: format_file_index(dir, index),
+            carousel: std::array::from_fn(|slot| (index + slot).checked_sub(SELECTED).and_then(|index| dir.get(index)).cloned()),
+        }
+    }
+}
+
+// the main panel, kept by the draw task. updates while something else is on screen are kept and shown next time
+pub struct NavPanel {
+    screen: Screen,
+    shown: bool,
+    // the selected name scrolls when it doesn't fit, the others get ellipsized
+    selected_name: String,
+    marquee: Marquee,
+    slide: Option<Slide>,
+}
+impl NavPanel {
+    pub fn new() -> NavPanel {
+        let top_bar = Node::Row { spacing: 0, children: vec![
+            Node::widget("folder_glyph", WidgetKind::Glyph(Glyph::Folder), 32, 20),
+            Node::widget("file_index", WidgetKind::label(""), 104, 20),
+            Node::widget("cloud_glyph", WidgetKind::Glyph(Glyph::Cloud), 36, 20),
+            Node::widget("weather", WidgetKind::label("?"), 34, 20),
+            Node::widget("clock_glyph", WidgetKind::Glyph(Glyph::Clock), 24, 20),
+            Node::widget("clock", WidgetKind::label(""), 62, 20),
+        ]};
+        let meta = Node::Row { spacing: 0, children: vec![
+            Node::Column { spacing: 0, children: vec![
+                Node::widget("size_title", WidgetKind::label(""), 80, 10),
+                Node::widget("size", WidgetKind::label(""), 80, 10),
+            ]},
+            Node::Column { spacing: 0, children: vec![
+                Node::widget("modified_title", WidgetKind::label(""), 180, 10),
+                Node::widget("modified", WidgetKind::label(""), 180, 10),
+            ]},
+        ]};
+        let rows = Node::Column { spacing: CAROUSEL_SPACING, children: CAROUSEL_IDS.iter()
+            .map(|&(icon_id, name_id)| Node::Row { spacing: 18, children: vec![
+                Node::widget(icon_id, WidgetKind::Icon(None), 32, CAROUSEL_ROW_HEIGHT),
+                Node::widget(name_id, WidgetKind::label(""), CAROUSEL_NAME_WIDTH, CAROUSEL_ROW_HEIGHT),
+            ]})
+            .collect()
+        };
+        // first row hangs off the top so the selected one lines up w/ its frame
+        let carousel = Node::Viewport { id: "carousel", size: Size::new(300, 140), child: Box::new(Node::Stack(vec![(Point::new(0, -CAROUSEL_PITCH), rows)])) };
+        let root = Node::Stack(vec![
+            (Point::new(10, 10), Node::widget("top_frame", WidgetKind::Frame { color: Role::Border, stroke_width: 1 }, 300, 40)),
+            (Point::new(40, 90), Node::widget("carousel_frame", WidgetKind::Frame { color: Role::Highlight, stroke_width: 2 }, 270, 140)),
+            (Point::new(50, 140), Node::widget("selected_frame", WidgetKind::Frame { color: Role::Accent, stroke_width: 2 }, 250, 40)),
+            (Point::new(14, 10), top_bar),
+            (Point::new(20, 31), Node::widget("path", WidgetKind::label(""), 280, 20)),
+            (Point::new(40, 60), meta),
+            (Point::new(10, 90), carousel),
+            // over the selected slot's name
+            (thumbnails::THUMBNAIL_COORDS, Node::widget("thumbnail", WidgetKind::Image(Vec::new()), thumbnails::THUMBNAIL_WIDTH as u32, thumbnails::THUMBNAIL_HEIGHT as u32)),
+        ]);
+        NavPanel { screen: Screen::new(root, WIDTH, HEIGHT), shown: false, selected_name: String::new(), marquee: Marquee::new(), slide: None }
+    }
+    pub fn update(&mut self, view: NavView, transition: Transition) {
+        self.screen.set("path", WidgetKind::label(&view.path));
+        self.screen.set("file_index", WidgetKind::label(&view.file_index));
+        for (entry, (icon_id, name_id)) in view.carousel.iter().zip(CAROUSEL_IDS) {
+            self.screen.set(icon_id, WidgetKind::Icon(entry.as_ref().map(|entry| entry.kind)));
+            if name_id != CAROUSEL_IDS[SELECTED].1 {
+                self.screen.set(name_id, WidgetKind::label(entry.as_ref().map(|entry| entry.name.as_str()).unwrap_or("")));
+            }
+        }
+        let selected_name = view.carousel[SELECTED].as_ref().map(|entry| entry.name.clone()).unwrap_or_default();
+        if selected_name != self.selected_name {
+            self.selected_name = selected_name;
+            self.marquee.restart();
+            self.screen.set(CAROUSEL_IDS[SELECTED].1, WidgetKind::Marquee { text: self.selected_name.clone(), offset: 0 });
+        }
+        // starts off showing where things were and slides to where they are now.
+        // a scroll mid slide carries on from wherever it had got to
+        let current = self.slide.as_ref().and_then(|slide| slide.offset()).unwrap_or(Point::zero());
+        let from = match transition {
+            Transition::None => None,
+            Transition::Up => Some(Point::new(0, (current.y - CAROUSEL_PITCH).max(-CAROUSEL_PITCH))),
+            Transition::Down => Some(Point::new(0, (current.y + CAROUSEL_PITCH).min(CAROUSEL_PITCH))),
+            Transition::Enter => Some(Point::new(WIDTH as i32, 0)),
+            Transition::Leave => Some(Point::new(-(WIDTH as i32), 0)),
+        };
+        self.slide = from.map(Slide::new);
+        self.screen.scroll("carousel", from.unwrap_or(Point::zero()));
+        // size/last modified of the selected entry, titles too so an empty dir shows nothing
+        let selected = view.carousel[SELECTED].as_ref();
+        self.screen.set("size_title", WidgetKind::label(if selected.is_some() { "Size" } else { "" }));
+        self.screen.set("size", WidgetKind::label(&selected.map(|entry| utils::format_bytes(entry.size)).unwrap_or_default()));
+        self.screen.set("modified_title", WidgetKind::label(if selected.is_some() { "Last modified" } else { "" }));
+        self.screen.set("modified", WidgetKind::label(&selected.map(|entry| entry.last_modified()).unwrap_or_default()));
+    }
+    pub fn set_clock(&mut self, time: &str) {
+        self.screen.set("clock", WidgetKind::label(time));
+    }
+    pub fn set_weather(&mut self, weather: &str) {
+        self.screen.set("weather", WidgetKind::label(weather));
+    }
+    // empty data clears it
+    pub fn set_thumbnail(&mut self, data: Vec<u8>) {
+        self.screen.set("thumbnail", WidgetKind::Image(data));
+    }
+    // after something else had the screen, everything gets redrawn
+    pub fn show(&mut self, fb: &mut [u8], width: usize, height: usize, theme: &Theme) -> Vec<Rectangle> {
+        self.shown = true;
+        self.screen.invalidate();
+        self.screen.paint(fb, width, height, theme)
+    }
+    // no point finishing a slide nobody sees
+    pub fn hide(&mut self) {
+        self.shown = false;
+        self.slide = None;
+        self.screen.scroll("carousel", Point::zero());
+    }
+    // while the carousel is sliding the draw task ticks a lot faster
+    pub fn animating(&self) -> bool {
+        self.shown && self.slide.is_some()
+    }
+    // the rects that changed, nothing while hidden
+    // moves the carousel and the selected name along, called every draw task tick
+    pub fn tick(&mut self, fb: &mut [u8], width: usize, height: usize, theme: &Theme) -> Vec<Rectangle> {
+        if !self.shown {
+            return Vec::new();
+        }
+        if let Some(slide) = &self.slide {
+            let offset = slide.offset();
+            if offset.is_none() {
+                self.slide = None;
+            }
+            self.screen.scroll("carousel", offset.unwrap_or(Point::zero()));
+        }
+        let text_width = fonts::text_width(&self.selected_name, theme.font);
+        if text_width > CAROUSEL_NAME_WIDTH {
+            let offset = self.marquee.offset(text_width);
+            self.screen.set(CAROUSEL_IDS[SELECTED].1, WidgetKind::Marquee { text: self.selected_name.clone(), offset });
+        }
+        self.screen.paint(fb, width, height, theme)
+    }
+    // everything gets redrawn next paint, for when the theme changes
+    pub fn invalidate(&mut self) {
+        self.screen.invalidate();
+    }
+    pub fn paint(&mut self, fb: &mut [u8], width: usize, height: usize, theme: &Theme) -> Vec<Rectangle> {
+        if self.shown {
+            self.screen.paint(fb, width, height, theme)
+        }
+        else {
+            // it'll all be redrawn by show() anyway
+            self.screen.invalidate();
+            Vec::new()
+        }
+    }
+}
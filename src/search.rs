@@ -0,0 +1,39 @@
+use crate::dir_model::DirModel;
+
+// typed on the keyboard, its title shows the first match as the query changes
+pub const TITLE: &str = "Search:";
+
+pub struct SearchData {
+    pub query: String,
+    // whatever is on the 2nd line of the 1st small screen, so it can be undrawn
+    pub drawn_query: String,
+}
+impl SearchData {
+    pub fn new() -> SearchData {
+        SearchData { query: String::new(), drawn_query: String::new() }
+    }
+    pub fn reset(&mut self) {
+        self.query.clear();
+    }
+    // for the small screen, ex. "/ep1_"
+    pub fn label(&self) -> String {
+        format!("/{}_", self.query)
+    }
+    // first entry starting with the query, otherwise the first one containing it
+    pub fn find(&self, dir: &DirModel) -> Option<usize> {
+        if self.query.is_empty() {
+            return None;
+        }
+        let names: Vec<String> = dir.entries.iter().map(|entry| entry.name.to_lowercase()).collect();
+        names.iter().position(|name| name.starts_with(&self.query))
+            .or_else(|| names.iter().position(|name| name.contains(&self.query)))
+    }
+    // keyboard title for the match so far
+    pub fn title(&self, dir: &DirModel) -> String {
+        match self.find(dir).and_then(|idx| dir.get(idx)) {
+            Some(entry) => format!("Found: {}", entry.name),
+            None if self.query.is_empty() => String::from(TITLE),
+            None => String::from("No match"),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{config::Config, dir_model::{Filter, SortKey}, modal::Modal, theme};
+
+const BRIGHTNESS_LEVELS: [u8; 5] = [20, 40, 60, 80, 100];
+// minutes, 0 is never
+const SLEEP_AFTER_CHOICES: [u64; 5] = [0, 1, 5, 10, 30];
+const VOLUME_STEPS: [u64; 4] = [1, 2, 5, 10];
+const SORT_KEYS: [SortKey; 4] = [SortKey::Name, SortKey::Size, SortKey::Modified, SortKey::Type];
+const FILTERS: [Filter; 3] = [Filter::All, Filter::NoHidden, Filter::Media];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Setting {
+    Brightness,
+    SleepAfter,
+    SortKey,
+    Filter,
+    Clock,
+    WeatherLocation,
+    VolumeStep,
+    Theme,
+    Animations,
+}
+const SETTINGS: [Setting; 9] = [
+    Setting::Brightness,
+    Setting::SleepAfter,
+    Setting::SortKey,
+    Setting::Filter,
+    Setting::Clock,
+    Setting::WeatherLocation,
+    Setting::VolumeStep,
+    Setting::Theme,
+    Setting::Animations,
+];
+impl Setting {
+    fn label(&self) -> &'static str {
+        match self {
+            Setting::Brightness => "Brightness",
+            Setting::SleepAfter => "Sleep after",
+            Setting::SortKey => "Sort by",
+            Setting::Filter => "Show",
+            Setting::Clock => "Clock",
+            Setting::WeatherLocation => "Weather for",
+            Setting::VolumeStep => "Volume step",
+            Setting::Theme => "Theme",
+            Setting::Animations => "Animations",
+        }
+    }
+    fn value(&self, config: &Config) -> String {
+        match self {
+            Setting::Brightness => format!("{}%", config.brightness),
+            Setting::SleepAfter if config.sleep_after == 0 => String::from("Never"),
+            Setting::SleepAfter => format!("{} min", config.sleep_after),
+            Setting::SortKey => String::from(match config.sort_key {
+                SortKey::Name => "Name",
+                SortKey::Size => "Size",
+                SortKey::Modified => "Last modified",
+                SortKey::Type => "Type",
+            }),
+            Setting::Filter => String::from(match config.filter {
+                Filter::All => "Everything",
+                Filter::NoHidden => "No hidden files",
+                Filter::Media => "Media only",
+            }),
+            Setting::Clock => String::from(if config.clock_24h { "24h" } else { "12h" }),
+            Setting::WeatherLocation => config.weather_location.clone(),
+            Setting::VolumeStep => format!("{}%", config.volume_step),
+            Setting::Theme => config.theme.clone(),
+            Setting::Animations => String::from(if config.animations { "On" } else { "Off" }),
+        }
+    }
+    // typed in w/ the keyboard instead of stepped through
+    pub fn is_text(&self) -> bool {
+        *self == Setting::WeatherLocation
+    }
+    // on to the next choice, wrapping round. text settings are left alone
+    pub fn step(&self, config: &mut Config) {
+        match self {
+            Setting::Brightness => config.brightness = next(&BRIGHTNESS_LEVELS, &config.brightness),
+            Setting::SleepAfter => config.sleep_after = next(&SLEEP_AFTER_CHOICES, &config.sleep_after),
+            Setting::SortKey => config.sort_key = next(&SORT_KEYS, &config.sort_key),
+            Setting::Filter => config.filter = next(&FILTERS, &config.filter),
+            Setting::Clock => config.clock_24h = !config.clock_24h,
+            Setting::WeatherLocation => (),
+            Setting::VolumeStep => config.volume_step = next(&VOLUME_STEPS, &config.volume_step),
+            Setting::Theme => config.theme = next(&theme::names(), &config.theme),
+            Setting::Animations => config.animations = !config.animations,
+        }
+    }
+}
+
+// the one after current, or the first if current isn't one of them (ex. an odd value from the config file)
+fn next<T: Clone + PartialEq>(choices: &[T], current: &T) -> T {
+    let idx = choices.iter().position(|choice| choice == current).map(|idx| idx + 1).unwrap_or(0);
+    choices[idx % choices.len()].clone()
+}
+
+// up/down pick a setting, select steps it on (or opens the keyboard for text ones), escape goes back.
+// every change is saved and applied straight away. shown as a modal w/ one option per setting
+pub struct SettingsMenu {
+    pub modal: Modal,
+}
+impl SettingsMenu {
+    pub fn new(config: &Config) -> SettingsMenu {
+        let mut menu = SettingsMenu { modal: Modal::new("Settings", &[]) };
+        menu.refresh(config);
+        menu
+    }
+    // after a change, the selection stays where it is
+    pub fn refresh(&mut self, config: &Config) {
+        self.modal.options = SETTINGS.iter().map(|setting| format!("{}: {}", setting.label(), setting.value(config))).collect();
+    }
+    pub fn selected_setting(&self) -> Setting {
+        SETTINGS[self.modal.selected]
+    }
+}
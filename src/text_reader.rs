@@ -0,0 +1,153 @@
+use std::{collections::HashMap, ffi::OsStr, os::unix::ffi::OsStrExt, path::{Path, PathBuf}};
+use embedded_graphics::{mono_font::ascii::FONT_6X10, prelude::*};
+
+use crate::{clear_screen, fonts, theme::Theme, utils, FramebufferDisplay, HEIGHT, WIDTH};
+
+// FONT_6X10 grid w/ a small margin around the panel
+const TEXT_MARGIN: usize = 4;
+pub const TEXT_COLS: usize = (WIDTH - 2 * TEXT_MARGIN) / 6;
+pub const TEXT_ROWS: usize = (HEIGHT - 2 * TEXT_MARGIN) / 10;
+const TAB_WIDTH: usize = 4;
+// drawn for anything none of the fonts have
+pub const FALLBACK_GLYPH: char = '?';
+// don't pull giant logs into memory on the pi
+const MAX_TEXT_BYTES: u64 = 2 * 1024 * 1024;
+const READING_POSITIONS_PATH: &str = "/home/yassin/cross_compiled/reading_positions.txt";
+
+pub fn is_text(path: &Path) -> bool {
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    let file_name = path.file_name().and_then(|name| name.to_str());
+    matches!((extension, file_name), (Some("txt" | "rs" | "sh" | "bashrc" | "md" | "log" | "toml" | "conf"), _) | (None, Some(".bashrc" | ".profile")))
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScrollStep {
+    Line,
+    Page,
+}
+
+pub struct TextReaderData {
+    pub path: PathBuf,
+    lines: Vec<String>,
+    top_line: usize,
+    pub step: ScrollStep,
+    // whatever is on the 1st small screen, so it can be undrawn
+    pub drawn_position: String,
+}
+impl TextReaderData {
+    pub fn new() -> TextReaderData {
+        TextReaderData { path: PathBuf::new(), lines: Vec::new(), top_line: 0, step: ScrollStep::Line, drawn_position: String::new() }
+    }
+    pub fn open(&mut self, path: &Path) -> Result<(), String> {
+        let bytes = read_capped(path).map_err(|e| format!("Text error: Could not read {}: {}", path.display(), e))?;
+        self.path = path.to_owned();
+        self.lines = wrap_text(&String::from_utf8_lossy(&bytes), (WIDTH - 2 * TEXT_MARGIN) as u32);
+        self.top_line = load_reading_positions().get(path).copied().unwrap_or(0).min(self.last_top_line());
+        self.step = ScrollStep::Line;
+        Ok(())
+    }
+    // remembers where we were for next time
+    pub fn close(&mut self) {
+        let mut positions = load_reading_positions();
+        positions.insert(self.path.clone(), self.top_line);
+        save_reading_positions(&positions);
+        self.lines = Vec::new();
+    }
+    fn last_top_line(&self) -> usize {
+        self.lines.len().saturating_sub(TEXT_ROWS)
+    }
+    fn step_lines(&self) -> usize {
+        match self.step {
+            ScrollStep::Line => 1,
+            ScrollStep::Page => TEXT_ROWS,
+        }
+    }
+    // false if already at the top/bottom, nothing to redraw
+    pub fn scroll(&mut self, down: bool) -> bool {
+        let new_top_line = if down {
+            (self.top_line + self.step_lines()).min(self.last_top_line())
+        }
+        else {
+            self.top_line.saturating_sub(self.step_lines())
+        };
+        let changed = new_top_line != self.top_line;
+        self.top_line = new_top_line;
+        changed
+    }
+    pub fn toggle_step(&mut self) {
+        self.step = match self.step {
+            ScrollStep::Line => ScrollStep::Page,
+            ScrollStep::Page => ScrollStep::Line,
+        };
+    }
+    // for the small screen, ex. "12/340 ln 3%"
+    pub fn position(&self) -> String {
+        let step = match self.step {
+            ScrollStep::Line => "ln",
+            ScrollStep::Page => "pg",
+        };
+        let percent = if self.last_top_line() == 0 { 100 } else { self.top_line * 100 / self.last_top_line() };
+        format!("{}/{} {} {}%", self.top_line + 1, self.lines.len().max(1), step, percent)
+    }
+    // full frame in framebuffer byte order, ready to be sent as a RawFrame
+    pub fn render(&self, theme: &Theme) -> Vec<u8> {
+        let mut frame = vec![0u8; WIDTH * HEIGHT * 2];
+        clear_screen(&mut frame, theme);
+        let mut display = FramebufferDisplay { buf: &mut frame, width: WIDTH, height: HEIGHT };
+        for (row, line) in self.lines.iter().skip(self.top_line).take(TEXT_ROWS).enumerate() {
+            let point = Point::new(TEXT_MARGIN as i32, (TEXT_MARGIN + row * 10) as i32);
+            fonts::draw_text(&mut display, line, point, &FONT_6X10, theme.text);
+        }
+        frame
+    }
+}
+
+fn read_capped(path: &Path) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)?.take(MAX_TEXT_BYTES).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+// word wraps to max_width px, words longer than a line get split. wide (cjk) chars take up two columns
+fn wrap_text(text: &str, max_width: u32) -> Vec<String> {
+    text.lines()
+        .flat_map(|paragraph| {
+            let paragraph: String = paragraph.chars()
+                .flat_map(|c| match c {
+                    '\t' => vec![' '; TAB_WIDTH],
+                    c if c.is_control() => vec![],
+                    c if !fonts::can_draw(c, &FONT_6X10) => vec![FALLBACK_GLYPH],
+                    c => vec![c],
+                })
+                .collect();
+            fonts::wrap(&paragraph, max_width, &FONT_6X10)
+        })
+        .collect()
+}
+
+// one "top_line<tab>path" per line, the path as its raw bytes since file names don't have to be utf-8
+fn load_reading_positions() -> HashMap<PathBuf, usize> {
+    let Ok(contents) = std::fs::read(READING_POSITIONS_PATH) else {
+        return HashMap::new();
+    };
+    contents.split(|&byte| byte == b'\n')
+        .filter_map(|line| {
+            let tab = line.iter().position(|&byte| byte == b'\t')?;
+            let top_line = std::str::from_utf8(&line[..tab]).ok()?.parse().ok()?;
+            Some((PathBuf::from(OsStr::from_bytes(&line[tab + 1..])), top_line))
+        })
+        .collect()
+}
+fn save_reading_positions(positions: &HashMap<PathBuf, usize>) {
+    let mut contents = Vec::new();
+    // a newline in the name would split the line, those just don't get remembered
+    for (path, top_line) in positions.iter().filter(|(path, _)| !path.as_os_str().as_bytes().contains(&b'\n')) {
+        contents.extend_from_slice(format!("{}\t", top_line).as_bytes());
+        contents.extend_from_slice(path.as_os_str().as_bytes());
+        contents.push(b'\n');
+    }
+    if let Err(e) = utils::write_atomic(READING_POSITIONS_PATH, &contents) {
+        println!("could not save reading positions: {:#?}", e);
+    }
+}
@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+use embedded_graphics::{mono_font::{ascii::{FONT_5X8, FONT_6X10, FONT_6X13, FONT_7X13, FONT_8X13}, MonoFont}, pixelcolor::Rgb565, prelude::*};
+
+use crate::widgets;
+
+// <name>.txt in here, same "key = value" lines as the config. keys left out come from the built in theme
+// w/ the same name, or the default one
+const THEMES_DIR: &str = "/home/yassin/cross_compiled/themes";
+const DEFAULT_ICON_DIR: &str = "/home/yassin/cross_compiled/filetype_icons";
+const BUILT_IN: [&str; 3] = ["default", "high_contrast", "dark"];
+
+// what a widget is colored as, looked up in the theme when it's drawn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Role {
+    Text,
+    // hints, unselected keys
+    Muted,
+    // selection outlines, progress bars
+    Accent,
+    // the carousel frame
+    Highlight,
+    Border,
+    // error toasts
+    Error,
+}
+
+#[derive(Clone)]
+pub struct Theme {
+    pub name: String,
+    pub background: Rgb565,
+    pub text: Rgb565,
+    pub muted: Rgb565,
+    pub accent: Rgb565,
+    pub highlight: Rgb565,
+    pub border: Rgb565,
+    pub error: Rgb565,
+    // no taller than widgets::MAX_FONT_HEIGHT
+    pub font: &'static MonoFont<'static>,
+    // the text box of the keyboard
+    pub large_font: &'static MonoFont<'static>,
+    // of every frame
+    pub radius: u32,
+    // folder_icon.rgb, video_icon.rgb etc, anything missing comes from the default set
+    pub icon_dir: PathBuf,
+}
+impl Theme {
+    fn built_in(name: &str) -> Theme {
+        let default = Theme {
+            name: name.to_string(),
+            background: Rgb565::CSS_DARK_GRAY,
+            text: Rgb565::WHITE,
+            muted: Rgb565::CSS_LIGHT_GRAY,
+            accent: Rgb565::CSS_SKY_BLUE,
+            highlight: Rgb565::CSS_LIGHT_BLUE,
+            border: Rgb565::WHITE,
+            error: Rgb565::CSS_ORANGE_RED,
+            font: &FONT_6X10,
+            large_font: &FONT_8X13,
+            radius: 10,
+            icon_dir: PathBuf::from(DEFAULT_ICON_DIR),
+        };
+        match name {
+            "high_contrast" => Theme {
+                background: Rgb565::BLACK,
+                muted: Rgb565::WHITE,
+                accent: Rgb565::YELLOW,
+                highlight: Rgb565::YELLOW,
+                error: Rgb565::RED,
+                radius: 0,
+                ..default
+            },
+            // black is off on an oled, so keep as little lit as possible
+            "dark" => Theme {
+                background: Rgb565::BLACK,
+                text: Rgb565::CSS_LIGHT_GRAY,
+                muted: Rgb565::CSS_DIM_GRAY,
+                accent: Rgb565::CSS_STEEL_BLUE,
+                highlight: Rgb565::CSS_DARK_SLATE_GRAY,
+                border: Rgb565::CSS_DIM_GRAY,
+                error: Rgb565::CSS_FIRE_BRICK,
+                ..default
+            },
+            _ => default,
+        }
+    }
+    pub fn load(name: &str) -> Theme {
+        let mut theme = Theme::built_in(name);
+        let path = Path::new(THEMES_DIR).join(format!("{}.txt", name));
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => {
+                if !BUILT_IN.contains(&name) {
+                    println!("no theme called {}, using the default one", name);
+                }
+                return theme;
+            }
+        };
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                println!("bad theme line: {}", line);
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let ok = match key {
+                "background" => parse_color(value).map(|color| theme.background = color).is_some(),
+                "text" => parse_color(value).map(|color| theme.text = color).is_some(),
+                "muted" => parse_color(value).map(|color| theme.muted = color).is_some(),
+                "accent" => parse_color(value).map(|color| theme.accent = color).is_some(),
+                "highlight" => parse_color(value).map(|color| theme.highlight = color).is_some(),
+                "border" => parse_color(value).map(|color| theme.border = color).is_some(),
+                "error" => parse_color(value).map(|color| theme.error = color).is_some(),
+                // the layouts are fixed, so anything taller than the smallest labels gets cut off
+                "font" => parse_font(value)
+                    .filter(|font| font.character_size.height <= widgets::MAX_FONT_HEIGHT)
+                    .map(|font| theme.font = font)
+                    .is_some(),
+                "large_font" => parse_font(value).map(|font| theme.large_font = font).is_some(),
+                "radius" => value.parse().map(|radius| theme.radius = radius).is_ok(),
+                "icon_dir" => {
+                    theme.icon_dir = PathBuf::from(value);
+                    true
+                }
+                _ => {
+                    println!("unknown theme key: {}", key);
+                    true
+                }
+            };
+            if !ok {
+                println!("bad {} in theme {}: {}", key, name, value);
+            }
+        }
+        theme
+    }
+    pub fn color(&self, role: Role) -> Rgb565 {
+        match role {
+            Role::Text => self.text,
+            Role::Muted => self.muted,
+            Role::Accent => self.accent,
+            Role::Highlight => self.highlight,
+            Role::Border => self.border,
+            Role::Error => self.error,
+        }
+    }
+    // icon file from this theme's set, falling back to the default set
+    pub fn icon(&self, file_name: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.icon_dir.join(file_name))
+            .or_else(|_| std::fs::read(Path::new(DEFAULT_ICON_DIR).join(file_name)))
+            .ok()
+    }
+}
+
+// built in ones first, then any others in the themes dir, for picking one in the settings
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = BUILT_IN.iter().map(|name| name.to_string()).collect();
+    let mut from_files: Vec<String> = std::fs::read_dir(THEMES_DIR)
+        .map(|entries| entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .filter(|name| !BUILT_IN.contains(&name.as_str()))
+            .collect())
+        .unwrap_or_default();
+    from_files.sort();
+    names.extend(from_files);
+    names
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::built_in("default")
+    }
+}
+
+// "#rrggbb"
+fn parse_color(value: &str) -> Option<Rgb565> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let rgb = u32::from_str_radix(hex, 16).ok()?;
+    let (r, g, b) = ((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8);
+    Some(Rgb565::new(r >> 3, g >> 2, b >> 3))
+}
+
+fn parse_font(value: &str) -> Option<&'static MonoFont<'static>> {
+    match value {
+        "5x8" => Some(&FONT_5X8),
+        "6x10" => Some(&FONT_6X10),
+        "6x13" => Some(&FONT_6X13),
+        "7x13" => Some(&FONT_7X13),
+        "8x13" => Some(&FONT_8X13),
+        _ => None,
+    }
+}
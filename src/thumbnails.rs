@@ -0,0 +1,131 @@
+use std::{collections::hash_map::DefaultHasher, fs::File, hash::{Hash, Hasher}, io::prelude::*, os::unix::fs::MetadataExt, path::{Path, PathBuf}, sync::{mpsc, Arc, Mutex}};
+use embedded_graphics::prelude::*;
+
+use crate::{DisplayState, DrawCommand, HEIGHT, WIDTH};
+
+// thumbnails go inside the right side of the selected carousel slot
+pub const THUMBNAIL_COORDS: Point = Point::new(248, 142);
+pub const THUMBNAIL_WIDTH: usize = 48;
+pub const THUMBNAIL_HEIGHT: usize = 36;
+const THUMBNAIL_CACHE_DIR: &str = "/home/yassin/cross_compiled/thumbnails";
+
+// files we can pull a frame out of
+pub fn is_playable(path: &Path) -> bool {
+    matches!(path.extension().and_then(|ext| ext.to_str()), Some("rgb565") | Some("raw"))
+}
+
+// main thread tells the thumbnail task what's selected, the task only draws if it still is
+pub struct ThumbnailRequester {
+    tx: mpsc::Sender<PathBuf>,
+    selected: Arc<Mutex<Option<PathBuf>>>,
+}
+impl ThumbnailRequester {
+    pub fn select(&self, path: Option<PathBuf>, draw_tx: &mpsc::Sender<DrawCommand>) {
+        // hold the lock while undrawing so a thumbnail for the old selection can't land after it
+        let mut selected = self.selected.lock().unwrap();
+        if *selected == path {
+            return;
+        }
+        *selected = path.clone();
+        draw_tx.send(DrawCommand::Thumbnail { data: Vec::new(), undraw: true }).unwrap();
+        drop(selected);
+
+        if let Some(path) = path && is_playable(&path) {
+            self.tx.send(path).unwrap();
+        }
+    }
+}
+
+pub fn start_thumbnail_task(draw_tx: mpsc::Sender<DrawCommand>, current_state: Arc<Mutex<DisplayState>>) -> ThumbnailRequester {
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+    let selected = Arc::new(Mutex::new(None));
+    let selected1 = selected.clone();
+    std::thread::spawn(move || {
+        thumbnail_task(rx, draw_tx, selected1, current_state);
+    });
+    ThumbnailRequester { tx, selected }
+}
+
+// generates (or loads) thumbnails in the background so scrolling isn't blocked on the sd card
+fn thumbnail_task(rx: mpsc::Receiver<PathBuf>, draw_tx: mpsc::Sender<DrawCommand>, selected: Arc<Mutex<Option<PathBuf>>>, current_state: Arc<Mutex<DisplayState>>) {
+    while let Ok(mut path) = rx.recv() {
+        // skip anything that was scrolled past while we were busy
+        while let Ok(newer_path) = rx.try_recv() {
+            path = newer_path;
+        }
+        let data = match load_or_generate_thumbnail(&path) {
+            Some(data) => data,
+            None => {
+                println!("could not generate thumbnail for {:?}", path);
+                continue;
+            }
+        };
+        // state first and let go of it, the main loop never holds it while taking the selection lock either
+        let navigating = matches!(*current_state.lock().unwrap(), DisplayState::Navigating);
+        let selected = selected.lock().unwrap();
+        if navigating && selected.as_ref() == Some(&path) {
+            draw_tx.send(DrawCommand::Thumbnail { data, undraw: false }).unwrap();
+        }
+    }
+}
+
+// cache is keyed by path + mtime, so a re-exported video gets a new thumbnail
+fn cache_path(path: &Path) -> Option<PathBuf> {
+    let mtime = std::fs::metadata(path).ok()?.mtime();
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    mtime.hash(&mut hasher);
+    Some(Path::new(THUMBNAIL_CACHE_DIR).join(format!("{:016x}.thumb", hasher.finish())))
+}
+
+// only reads the cache, for redraws in the draw task
+pub fn load_cached_thumbnail(path: &Path) -> Option<Vec<u8>> {
+    if !is_playable(path) {
+        return None;
+    }
+    let data = std::fs::read(cache_path(path)?).ok()?;
+    if data.len() == THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT * 2 { Some(data) } else { None }
+}
+
+// makes sure it's cached and returns where, for the library index
+pub fn ensure_thumbnail(path: &Path) -> Option<PathBuf> {
+    load_or_generate_thumbnail(path)?;
+    cache_path(path)
+}
+fn load_or_generate_thumbnail(path: &Path) -> Option<Vec<u8>> {
+    if let Some(data) = load_cached_thumbnail(path) {
+        return Some(data);
+    }
+    let data = generate_thumbnail(path)?;
+    let cache_path = cache_path(path)?;
+    if let Err(e) = std::fs::create_dir_all(THUMBNAIL_CACHE_DIR).and_then(|_| std::fs::write(&cache_path, &data)) {
+        println!("could not cache thumbnail: {:#?}", e);
+    }
+    Some(data)
+}
+
+// takes a frame ~10% in (skips black intros), nearest neighbour downscale
+// frames are already in framebuffer byte order so pixels are copied as-is
+fn generate_thumbnail(path: &Path) -> Option<Vec<u8>> {
+    let frame_bytes = WIDTH * HEIGHT * 2;
+    let mut video_file = File::open(path).ok()?;
+    let total_frames = video_file.metadata().ok()?.len() / frame_bytes as u64;
+    if total_frames == 0 {
+        return None;
+    }
+    let representative_frame = total_frames / 10;
+    let mut frame = vec![0u8; frame_bytes];
+    video_file.seek(std::io::SeekFrom::Start(representative_frame * frame_bytes as u64)).ok()?;
+    video_file.read_exact(&mut frame).ok()?;
+
+    let mut thumbnail = Vec::with_capacity(THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT * 2);
+    for y in 0..THUMBNAIL_HEIGHT {
+        let src_y = y * HEIGHT / THUMBNAIL_HEIGHT;
+        for x in 0..THUMBNAIL_WIDTH {
+            let src_x = x * WIDTH / THUMBNAIL_WIDTH;
+            let idx = (src_y * WIDTH + src_x) * 2;
+            thumbnail.extend_from_slice(&frame[idx..idx + 2]);
+        }
+    }
+    Some(thumbnail)
+}
@@ -0,0 +1,49 @@
+use std::time::Duration;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+
+use crate::{fonts, theme::{Role, Theme}, widgets::{Node, Screen, WidgetKind}};
+
+// along the bottom, over whatever's showing
+pub const BOUNDS: Rectangle = Rectangle::new(Point::new(20, 192), Size::new(280, 38));
+const PADDING: u32 = 8;
+// anything longer gets cut off w/ "..."
+const MAX_LINES: usize = 2;
+
+// short message over the current screen that goes away on its own, nothing to answer so the state doesn't change
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ToastKind {
+    Info,
+    Error,
+}
+impl ToastKind {
+    // errors stay up longer, they tend to be longer
+    pub fn duration(&self) -> Duration {
+        match self {
+            ToastKind::Info => Duration::from_secs(2),
+            ToastKind::Error => Duration::from_secs(5),
+        }
+    }
+}
+
+// pixels the size of BOUNDS, handed to the back buffer as an overlay
+pub fn render(message: &str, kind: ToastKind, theme: &Theme) -> Vec<u8> {
+    let (width, height) = (BOUNDS.size.width as usize, BOUNDS.size.height as usize);
+    let text_width = BOUNDS.size.width - PADDING * 2;
+    let mut lines = fonts::wrap(message, text_width, theme.font);
+    if lines.len() > MAX_LINES {
+        let last = format!("{} {}", lines[MAX_LINES - 1], lines[MAX_LINES]);
+        lines.truncate(MAX_LINES - 1);
+        lines.push(fonts::ellipsize(&last, text_width, theme.font));
+    }
+    let border = match kind {
+        ToastKind::Info => Role::Accent,
+        ToastKind::Error => Role::Error,
+    };
+    let mut screen = Screen::new(Node::Stack(vec![
+        (Point::zero(), Node::widget("border", WidgetKind::Frame { color: border, stroke_width: 2 }, BOUNDS.size.width, BOUNDS.size.height)),
+        (Point::new(PADDING as i32, 0), Node::widget("message", WidgetKind::label(&lines.join("\n")), text_width, BOUNDS.size.height)),
+    ]), width, height);
+    let mut pixels = vec![0u8; width * height * 2];
+    screen.paint(&mut pixels, width, height, theme);
+    pixels
+}
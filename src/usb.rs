@@ -0,0 +1,234 @@
+use std::{fs::File, io::Read, os::fd::{FromRawFd, OwnedFd}, path::{Path, PathBuf}, process::Command, sync::mpsc, thread, time::Duration};
+
+use crate::ButtonEvent;
+
+// made under the media root while a stick is plugged in, shows up as a top level "USB" entry
+pub const MOUNT_DIR_NAME: &str = "USB";
+// only if the uevent socket can't be opened
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// a stick fires a burst of uevents (disk, then each partition), and a remove comes in before sysfs is cleaned up
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+pub struct UsbState {
+    // /sys normally, point it at a fake tree to test w/o a stick
+    sysfs_root: PathBuf,
+    pub mount_point: PathBuf,
+    // dev node of the partition we'd mount, ex. /dev/sda1
+    pub device: Option<PathBuf>,
+    pub mounted: bool,
+}
+impl UsbState {
+    pub fn new(sysfs_root: &Path, media_root: &Path) -> UsbState {
+        UsbState { sysfs_root: sysfs_root.to_owned(), mount_point: mount_point(media_root), device: None, mounted: false }
+    }
+    // re-reads sysfs, makes/removes the mount point to match. true if a stick came or went
+    pub fn refresh(&mut self) -> bool {
+        let device = find_device(&self.sysfs_root);
+        if device == self.device {
+            return false;
+        }
+        // pulled out (or swapped) while mounted, lazy so it can't fail on busy files
+        if self.mounted {
+            self.unmount_with(&["-l"]);
+        }
+        match &device {
+            Some(device) => {
+                println!("usb storage plugged in: {}", device.display());
+                if let Err(e) = std::fs::create_dir_all(&self.mount_point) {
+                    println!("could not make usb mount point: {:#?}", e);
+                }
+            }
+            None => {
+                println!("usb storage removed");
+                // only removes it if it's empty, so nothing under it gets lost if the unmount didn't happen
+                let _ = std::fs::remove_dir(&self.mount_point);
+            }
+        }
+        self.device = device;
+        true
+    }
+    pub fn is_mount_point(&self, path: &Path) -> bool {
+        self.device.is_some() && path == self.mount_point
+    }
+    // read only, we never write to people's sticks
+    pub fn mount(&mut self) -> Result<(), String> {
+        if self.mounted {
+            return Ok(());
+        }
+        let Some(device) = &self.device else {
+            return Err(String::from("USB error: No USB storage plugged in."));
+        };
+        let output = Command::new("mount")
+            .arg("-o")
+            .arg("ro")
+            .arg(device)
+            .arg(&self.mount_point)
+            .output()
+            .map_err(|e| format!("USB error: Could not run mount: {}", e))?;
+        if !output.status.success() {
+            return Err(format!("USB error: Could not mount {}: {}", device.display(), String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        self.mounted = true;
+        Ok(())
+    }
+    pub fn unmount(&mut self) {
+        if self.mounted {
+            self.unmount_with(&[]);
+        }
+    }
+    fn unmount_with(&mut self, args: &[&str]) {
+        match Command::new("umount").args(args).arg(&self.mount_point).output() {
+            Ok(output) if output.status.success() => self.mounted = false,
+            Ok(output) => println!("could not unmount usb: {}", String::from_utf8_lossy(&output.stderr).trim()),
+            Err(e) => println!("could not run umount: {:#?}", e),
+        }
+    }
+}
+
+pub fn mount_point(media_root: &Path) -> PathBuf {
+    media_root.join(MOUNT_DIR_NAME)
+}
+
+// first partition of the first usb disk, or the whole disk if it isn't partitioned
+pub fn find_device(sysfs_root: &Path) -> Option<PathBuf> {
+    let block_dir = sysfs_root.join("block");
+    let mut disks: Vec<String> = std::fs::read_dir(&block_dir).ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("sd"))
+        .collect();
+    disks.sort();
+    for disk in disks {
+        let disk_dir = block_dir.join(&disk);
+        if !is_usb(&disk_dir) {
+            continue;
+        }
+        // partitions are subdirs w/ a "partition" file in them
+        let mut partitions: Vec<String> = std::fs::read_dir(&disk_dir).into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().join("partition").exists())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        partitions.sort();
+        let name = partitions.into_iter().next().unwrap_or(disk);
+        return Some(Path::new("/dev").join(name));
+    }
+    None
+}
+fn is_usb(disk_dir: &Path) -> bool {
+    // /sys/block/sdX links into the device tree, usb disks hang off a .../usbN/... node.
+    // some sticks don't set removable, some card readers only set removable, so either counts
+    let on_usb_bus = std::fs::canonicalize(disk_dir)
+        .map(|target| target.to_string_lossy().contains("/usb"))
+        .unwrap_or(false);
+    let removable = std::fs::read_to_string(disk_dir.join("removable"))
+        .map(|removable| removable.trim() == "1")
+        .unwrap_or(false);
+    on_usb_bus || removable
+}
+
+// waits on kernel uevents (what udev listens to), sysfs is only re-read after block devices come or go.
+// sends UsbChanged when a stick is plugged in or pulled out
+pub fn usb_task(tx: mpsc::Sender<ButtonEvent>, sysfs_root: PathBuf) {
+    let mut device = find_device(&sysfs_root);
+    if device.is_some() {
+        tx.send(ButtonEvent::UsbChanged).unwrap();
+    }
+    let mut uevents = match open_uevent_socket() {
+        Ok(socket) => Some(File::from(socket)),
+        Err(e) => {
+            println!("could not listen for uevents, polling for usb instead: {:#?}", e);
+            None
+        }
+    };
+    // the biggest uevents are a couple KB
+    let mut buf = vec![0u8; 8192];
+    loop {
+        match &mut uevents {
+            Some(socket) => {
+                match socket.read(&mut buf) {
+                    Ok(len) if is_block_uevent(&buf[..len]) => thread::sleep(SETTLE_TIME),
+                    Ok(_) => continue,
+                    Err(e) => {
+                        println!("uevent socket failed, polling for usb instead: {:#?}", e);
+                        uevents = None;
+                    }
+                }
+            }
+            None => thread::sleep(POLL_INTERVAL),
+        }
+        let new_device = find_device(&sysfs_root);
+        if new_device != device {
+            device = new_device;
+            tx.send(ButtonEvent::UsbChanged).unwrap();
+        }
+    }
+}
+
+// netlink socket on the kernel's uevent broadcast group
+fn open_uevent_socket() -> std::io::Result<OwnedFd> {
+    // SAFETY: plain syscalls, the fd is owned straight away so it's closed on every error path
+    unsafe {
+        let fd = libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_KOBJECT_UEVENT);
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let socket = OwnedFd::from_raw_fd(fd);
+        let mut addr: libc::sockaddr_nl = std::mem::zeroed();
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        addr.nl_groups = 1;
+        if libc::bind(fd, &addr as *const libc::sockaddr_nl as *const libc::sockaddr, std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(socket)
+    }
+}
+
+// "add@/devices/...\0ACTION=add\0...SUBSYSTEM=block\0...", nul separated
+fn is_block_uevent(message: &[u8]) -> bool {
+    message.split(|byte| *byte == 0).any(|field| field == b"SUBSYSTEM=block")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a fresh dir under /tmp laid out like /sys/block. no "/usb" in the name, is_usb would take it for a usb device path
+    fn fake_sysfs(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("fake_sysfs_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("block")).unwrap();
+        root
+    }
+
+    #[test]
+    fn finds_first_partition_of_removable_disk() {
+        let root = fake_sysfs("partition");
+        let disk = root.join("block/sda");
+        std::fs::create_dir_all(disk.join("sda2")).unwrap();
+        std::fs::create_dir_all(disk.join("sda1")).unwrap();
+        std::fs::write(disk.join("removable"), "1\n").unwrap();
+        std::fs::write(disk.join("sda1/partition"), "1\n").unwrap();
+        std::fs::write(disk.join("sda2/partition"), "2\n").unwrap();
+        // the sd card isn't an sdX disk, so it's never picked up
+        std::fs::create_dir_all(root.join("block/mmcblk0")).unwrap();
+        assert_eq!(find_device(&root), Some(PathBuf::from("/dev/sda1")));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn ignores_fixed_disks() {
+        let root = fake_sysfs("fixed");
+        std::fs::create_dir_all(root.join("block/sda")).unwrap();
+        std::fs::write(root.join("block/sda/removable"), "0\n").unwrap();
+        assert_eq!(find_device(&root), None);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn only_block_uevents_count() {
+        assert!(is_block_uevent(b"add@/devices/platform/usb1/1-1/block/sda\0ACTION=add\0SUBSYSTEM=block\0DEVNAME=sda\0"));
+        assert!(!is_block_uevent(b"add@/devices/platform/usb1/1-1\0ACTION=add\0SUBSYSTEM=usb\0"));
+    }
+}
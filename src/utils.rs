@@ -24,6 +24,18 @@ pub fn format_timecode(current_frames: u64, total_frames: u64, fps: u64) -> Stri
         )
     }
 }
+// single duration, used by the playback hud
+pub fn format_duration(total_seconds: u64) -> String {
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+
+    if hours > 0 {
+        format!("{:01}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:01}:{:02}", minutes, seconds)
+    }
+}
 pub fn format_bytes(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = 1024 * KB;
@@ -36,3 +48,9 @@ pub fn format_bytes(bytes: u64) -> String {
         _ => format!("{}B", bytes)
     }
 }
+// written next to it then renamed over it, so losing power halfway leaves the old file and not half a new one
+pub fn write_atomic(path: &str, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let tmp_path = format!("{}.tmp", path);
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, path)
+}
@@ -0,0 +1,350 @@
+use std::time::{Duration, Instant};
+use embedded_graphics::{prelude::*, primitives::{PrimitiveStyle, PrimitiveStyleBuilder, Rectangle, RoundedRectangle}};
+
+use crate::{draw, draw_icon, fonts, theme::{Role, Theme}, FramebufferDisplay, IconKind};
+
+// icons are drawn at a fixed size, centered vertically in their bounds
+const ICON_HEIGHT: u32 = 20;
+pub const LIST_ROW_HEIGHT: u32 = 18;
+// of a theme's font, the smallest labels (size/modified in the navigator) are this tall
+pub const MAX_FONT_HEIGHT: u32 = 10;
+// space between the end of a scrolling text and its start coming round again
+pub const MARQUEE_GAP: u32 = 40;
+// sits still for this long each time round so the start can be read
+const MARQUEE_PAUSE: Duration = Duration::from_millis(1500);
+// px per second
+const MARQUEE_SPEED: u64 = 30;
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum WidgetKind {
+    // left aligned, centered vertically, ellipsized to its bounds
+    Label { text: String, color: Role },
+    // one line, scrolled left by offset px and wrapping round when it's too wide, offset is ignored if it fits
+    Marquee { text: String, offset: u32 },
+    Icon(Option<IconKind>),
+    Glyph(Glyph),
+    // outline filling its bounds, rounded by the theme's radius
+    Frame { color: Role, stroke_width: u32 },
+    // outlined when selected, like the modal options
+    Button { text: String, selected: bool },
+    // one row per item, the selected one outlined
+    List { items: Vec<String>, selected: usize },
+    Progress { percent: u64 },
+    // the keyboard's, outlined w/ a cursor after the text in the large font. only the tail shows if it's too long
+    TextBox(String),
+    // raw framebuffer bytes the size of its bounds, empty draws nothing
+    Image(Vec<u8>),
+}
+impl WidgetKind {
+    pub fn label(text: &str) -> WidgetKind {
+        WidgetKind::Label { text: text.to_string(), color: Role::Text }
+    }
+}
+
+// the line drawn icons from draw.rs, drawn at the top left of their bounds
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Glyph {
+    Folder,
+    Cloud,
+    Clock,
+}
+
+pub struct Widget {
+    pub id: &'static str,
+    pub kind: WidgetKind,
+    // as laid out, before any scrolling
+    pub bounds: Rectangle,
+    // index into the screen's viewports
+    viewport: Option<usize>,
+}
+impl Widget {
+    // only touches pixels inside clip, except glyphs which are small enough to just redraw whole.
+    // bounds is where it is right now, which is off its laid out bounds while its viewport is scrolled
+    fn draw(&self, fb: &mut [u8], width: usize, height: usize, bounds: Rectangle, clip: &Rectangle, theme: &Theme) {
+        let mut display = FramebufferDisplay { buf: fb, width, height };
+        let top_left = bounds.top_left;
+        let size = bounds.size;
+        let line_height = theme.font.character_size.height;
+        match &self.kind {
+            WidgetKind::Label { text, color } => {
+                let lines = text.lines().count().max(1) as u32;
+                let top = top_left + Point::new(0, (size.height.saturating_sub(lines * line_height) / 2) as i32);
+                let mut clipped = display.clipped(&clip.intersection(&bounds));
+                fonts::draw_text(&mut clipped, &fonts::ellipsize(text, size.width, theme.font), top, theme.font, theme.color(*color));
+            }
+            WidgetKind::Marquee { text, offset } => {
+                let top = top_left + Point::new(0, (size.height.saturating_sub(line_height) / 2) as i32);
+                let mut clipped = display.clipped(&clip.intersection(&bounds));
+                let text_width = fonts::text_width(text, theme.font);
+                if text_width <= size.width {
+                    fonts::draw_text(&mut clipped, text, top, theme.font, theme.text);
+                }
+                else {
+                    // the tail going out and the start coming back in
+                    let scrolled = top - Point::new(*offset as i32, 0);
+                    fonts::draw_text(&mut clipped, text, scrolled, theme.font, theme.text);
+                    fonts::draw_text(&mut clipped, text, scrolled + Point::new((text_width + MARQUEE_GAP) as i32, 0), theme.font, theme.text);
+                }
+            }
+            WidgetKind::Icon(Some(kind)) => {
+                let mut clipped = display.clipped(&clip.intersection(&bounds));
+                draw_icon(top_left + Point::new(0, (size.height.saturating_sub(ICON_HEIGHT) / 2) as i32), &mut clipped, *kind, theme);
+            }
+            WidgetKind::Icon(None) => (),
+            WidgetKind::Glyph(Glyph::Folder) => draw::draw_folder(&mut display, width, height, top_left, theme.text),
+            WidgetKind::Glyph(Glyph::Cloud) => draw::draw_cloud(&mut display, width, height, top_left, theme.text),
+            WidgetKind::Glyph(Glyph::Clock) => draw::draw_clock(&mut display, width, height, top_left, theme.text),
+            WidgetKind::Frame { color, stroke_width } => {
+                let mut clipped = display.clipped(clip);
+                let style = PrimitiveStyleBuilder::new()
+                    .stroke_width(*stroke_width)
+                    .stroke_color(theme.color(*color))
+                    .build();
+                RoundedRectangle::with_equal_corners(bounds, Size::new(theme.radius, theme.radius))
+                    .into_styled(style)
+                    .draw(&mut clipped)
+                    .unwrap();
+            }
+            WidgetKind::Button { text, selected } => {
+                let mut clipped = display.clipped(&clip.intersection(&bounds));
+                if *selected {
+                    bounds
+                        .into_styled(PrimitiveStyle::with_stroke(theme.accent, 1))
+                        .draw(&mut clipped)
+                        .unwrap();
+                }
+                let text_top = top_left + Point::new(6, (size.height.saturating_sub(line_height) / 2) as i32);
+                fonts::draw_text(&mut clipped, &fonts::ellipsize(text, size.width.saturating_sub(6), theme.font), text_top, theme.font, theme.text);
+            }
+            WidgetKind::List { items, selected } => {
+                let mut clipped = display.clipped(&clip.intersection(&bounds));
+                for (idx, item) in items.iter().enumerate() {
+                    let row_top = top_left + Point::new(0, (idx as u32 * LIST_ROW_HEIGHT) as i32);
+                    if idx == *selected {
+                        Rectangle::new(row_top, Size::new(size.width, LIST_ROW_HEIGHT))
+                            .into_styled(PrimitiveStyle::with_stroke(theme.accent, 1))
+                            .draw(&mut clipped)
+                            .unwrap();
+                    }
+                    let text_top = row_top + Point::new(6, (LIST_ROW_HEIGHT.saturating_sub(line_height) / 2) as i32);
+                    fonts::draw_text(&mut clipped, &fonts::ellipsize(item, size.width.saturating_sub(12), theme.font), text_top, theme.font, theme.text);
+                }
+            }
+            WidgetKind::Progress { percent } => {
+                let mut clipped = display.clipped(&clip.intersection(&bounds));
+                let radius = theme.radius.min(4);
+                RoundedRectangle::with_equal_corners(bounds, Size::new(radius, radius))
+                    .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+                    .draw(&mut clipped)
+                    .unwrap();
+                let filled = (size.width.saturating_sub(4) as u64 * (*percent).min(100) / 100) as u32;
+                if filled > 0 {
+                    Rectangle::new(top_left + Point::new(2, 2), Size::new(filled, size.height.saturating_sub(4)))
+                        .into_styled(PrimitiveStyle::with_fill(theme.accent))
+                        .draw(&mut clipped)
+                        .unwrap();
+                }
+            }
+            WidgetKind::TextBox(text) => {
+                let mut clipped = display.clipped(&clip.intersection(&bounds));
+                bounds
+                    .into_styled(PrimitiveStyle::with_stroke(theme.border, 1))
+                    .draw(&mut clipped)
+                    .unwrap();
+                let mut shown = format!("{}_", text);
+                while fonts::text_width(&shown, theme.large_font) > size.width.saturating_sub(8) {
+                    shown.remove(0);
+                }
+                let text_top = top_left + Point::new(4, (size.height.saturating_sub(theme.large_font.character_size.height) / 2) as i32);
+                fonts::draw_text(&mut clipped, &shown, text_top, theme.large_font, theme.text);
+            }
+            WidgetKind::Image(data) => {
+                if data.len() != (size.width * size.height * 2) as usize {
+                    return;
+                }
+                // straight copy, rows clipped to the dirty rect
+                let area = clip.intersection(&bounds);
+                let row_bytes = area.size.width as usize * 2;
+                for y in 0..area.size.height as i32 {
+                    let src_x = (area.top_left.x - top_left.x) as usize;
+                    let src_y = (area.top_left.y - top_left.y + y) as usize;
+                    let src = (src_y * size.width as usize + src_x) * 2;
+                    let dst = ((area.top_left.y + y) as usize * width + area.top_left.x as usize) * 2;
+                    fb[dst..dst + row_bytes].copy_from_slice(&data[src..src + row_bytes]);
+                }
+            }
+        }
+    }
+}
+
+// timing for a scrolling text, the offset comes from how long it's been going so it's the same however often it's drawn
+pub struct Marquee {
+    started: Instant,
+}
+impl Marquee {
+    pub fn new() -> Marquee {
+        Marquee { started: Instant::now() }
+    }
+    // back to the start, for when the text changes
+    pub fn restart(&mut self) {
+        self.started = Instant::now();
+    }
+    // px to scroll a text this wide by right now
+    pub fn offset(&self, text_width: u32) -> u32 {
+        let loop_width = (text_width + MARQUEE_GAP) as u64;
+        let period = MARQUEE_PAUSE.as_millis() as u64 + loop_width * 1000 / MARQUEE_SPEED;
+        let elapsed = self.started.elapsed().as_millis() as u64 % period;
+        (elapsed.saturating_sub(MARQUEE_PAUSE.as_millis() as u64) * MARQUEE_SPEED / 1000).min(loop_width) as u32
+    }
+}
+
+// how a screen is declared, turned into flat widgets w/ bounds once by Screen::new
+pub enum Node {
+    Widget { id: &'static str, kind: WidgetKind, size: Size },
+    // left to right, top aligned
+    Row { spacing: u32, children: Vec<Node> },
+    // top to bottom, left aligned
+    Column { spacing: u32, children: Vec<Node> },
+    // each child at its own offset, later ones drawn over earlier ones
+    Stack(Vec<(Point, Node)>),
+    // a window onto its child, which can be moved around inside it w/ Screen::scroll. nothing outside it gets drawn
+    Viewport { id: &'static str, size: Size, child: Box<Node> },
+}
+impl Node {
+    pub fn widget(id: &'static str, kind: WidgetKind, width: u32, height: u32) -> Node {
+        Node::Widget { id, kind, size: Size::new(width, height) }
+    }
+    fn size(&self) -> Size {
+        match self {
+            Node::Widget { size, .. } => *size,
+            Node::Row { spacing, children } => {
+                let width = children.iter().map(|child| child.size().width).sum::<u32>() + spacing * children.len().saturating_sub(1) as u32;
+                Size::new(width, children.iter().map(|child| child.size().height).max().unwrap_or(0))
+            }
+            Node::Column { spacing, children } => {
+                let height = children.iter().map(|child| child.size().height).sum::<u32>() + spacing * children.len().saturating_sub(1) as u32;
+                Size::new(children.iter().map(|child| child.size().width).max().unwrap_or(0), height)
+            }
+            // anything hanging off the top/left doesn't count
+            Node::Stack(children) => children.iter()
+                .map(|(offset, child)| Size::new((offset.x + child.size().width as i32).max(0) as u32, (offset.y + child.size().height as i32).max(0) as u32))
+                .fold(Size::zero(), |size, child_size| size.component_max(child_size)),
+            Node::Viewport { size, .. } => *size,
+        }
+    }
+    fn layout(self, top_left: Point, viewport: Option<usize>, out: &mut Layout) {
+        match self {
+            Node::Widget { id, kind, size } => out.widgets.push(Widget { id, kind, bounds: Rectangle::new(top_left, size), viewport }),
+            Node::Row { spacing, children } => {
+                let mut x = top_left.x;
+                for child in children {
+                    let width = child.size().width;
+                    child.layout(Point::new(x, top_left.y), viewport, out);
+                    x += (width + spacing) as i32;
+                }
+            }
+            Node::Column { spacing, children } => {
+                let mut y = top_left.y;
+                for child in children {
+                    let height = child.size().height;
+                    child.layout(Point::new(top_left.x, y), viewport, out);
+                    y += (height + spacing) as i32;
+                }
+            }
+            Node::Stack(children) => {
+                for (offset, child) in children {
+                    child.layout(top_left + offset, viewport, out);
+                }
+            }
+            // no nesting them, the inner one just takes over
+            Node::Viewport { id, size, child } => {
+                out.viewports.push(Viewport { id, bounds: Rectangle::new(top_left, size), offset: Point::zero() });
+                child.layout(top_left, Some(out.viewports.len() - 1), out);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Layout {
+    widgets: Vec<Widget>,
+    viewports: Vec<Viewport>,
+}
+
+struct Viewport {
+    id: &'static str,
+    bounds: Rectangle,
+    offset: Point,
+}
+
+// a laid out screen. widgets are changed through set(), paint() only redraws what changed
+pub struct Screen {
+    widgets: Vec<Widget>,
+    viewports: Vec<Viewport>,
+    dirty: Vec<Rectangle>,
+    size: Size,
+}
+impl Screen {
+    pub fn new(root: Node, width: usize, height: usize) -> Screen {
+        let mut layout = Layout::default();
+        root.layout(Point::zero(), None, &mut layout);
+        let size = Size::new(width as u32, height as u32);
+        Screen { widgets: layout.widgets, viewports: layout.viewports, dirty: vec![Rectangle::new(Point::zero(), size)], size }
+    }
+    // no-op if it's already showing that
+    pub fn set(&mut self, id: &str, kind: WidgetKind) {
+        let Some(idx) = self.widgets.iter().position(|widget| widget.id == id) else {
+            println!("no widget called {}", id);
+            return;
+        };
+        if self.widgets[idx].kind != kind {
+            self.widgets[idx].kind = kind;
+            let (_, area) = self.placement(&self.widgets[idx]);
+            self.dirty.push(area);
+        }
+    }
+    // moves everything in a viewport by offset from where it was laid out, no-op if it's already there
+    pub fn scroll(&mut self, id: &str, offset: Point) {
+        let Some(viewport) = self.viewports.iter_mut().find(|viewport| viewport.id == id) else {
+            println!("no viewport called {}", id);
+            return;
+        };
+        if viewport.offset != offset {
+            viewport.offset = offset;
+            self.dirty.push(viewport.bounds);
+        }
+    }
+    // where a widget is drawn right now and the part of that which can show
+    fn placement(&self, widget: &Widget) -> (Rectangle, Rectangle) {
+        match widget.viewport.map(|idx| &self.viewports[idx]) {
+            Some(viewport) => {
+                let bounds = widget.bounds.translate(viewport.offset);
+                (bounds, bounds.intersection(&viewport.bounds))
+            }
+            None => (widget.bounds, widget.bounds),
+        }
+    }
+    // next paint redraws everything, for when something else was drawn over the screen
+    pub fn invalidate(&mut self) {
+        self.dirty = vec![Rectangle::new(Point::zero(), self.size)];
+    }
+    // clears each dirty rect to the background, then redraws every widget touching it in declaration order.
+    // returns the rects it touched so they can be flushed
+    pub fn paint(&mut self, fb: &mut [u8], width: usize, height: usize, theme: &Theme) -> Vec<Rectangle> {
+        let dirty = std::mem::take(&mut self.dirty);
+        for &rect in &dirty {
+            let mut display = FramebufferDisplay { buf: fb, width, height };
+            rect.into_styled(PrimitiveStyle::with_fill(theme.background))
+                .draw(&mut display)
+                .unwrap();
+            for widget in &self.widgets {
+                let (bounds, area) = self.placement(widget);
+                let clip = area.intersection(&rect);
+                if !clip.is_zero_sized() {
+                    widget.draw(fb, width, height, bounds, &clip, theme);
+                }
+            }
+        }
+        dirty
+    }
+}